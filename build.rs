@@ -0,0 +1,185 @@
+//! Generates the example tests of every day from its manifest in `data/examples/<day>.json`.
+//!
+//! A manifest lists one case per example and part:
+//!
+//! ```json
+//! {
+//!     "cases": [
+//!         { "part": 1, "expected": 143 },
+//!         { "part": 2, "example": 2, "expected": "123" },
+//!         { "part": 1, "name": "small_grid", "function": "safety_factor", "params": [11, 7], "expected": 12 }
+//!     ]
+//! }
+//! ```
+//!
+//!  - `example` reads `data/examples/<day>-<example>.txt` instead of `data/examples/<day>.txt`.
+//!  - `function` calls another solution function than `part_one` / `part_two`, passing `params` after the input.
+//!    Days with a `parse` function in `solution!` pass the parsed input instead.
+//!  - `expected` is compared against the displayed answer, `null` expects `None`.
+//!
+//! The generated tests are pulled into a solution with `advent_of_code::example_tests!()`.
+use std::{collections::HashMap, env, fmt::Write, fs, path::Path, str::FromStr};
+use tinyjson::JsonValue;
+
+const EXAMPLES_DIR: &str = "data/examples";
+
+fn main() {
+    println!("cargo:rerun-if-changed={EXAMPLES_DIR}");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    for day in 1..=25 {
+        let manifest_path = Path::new(EXAMPLES_DIR).join(format!("{day:02}.json"));
+
+        let tests = if manifest_path.exists() {
+            let manifest = fs::read_to_string(&manifest_path).unwrap();
+            generate_tests(&manifest).unwrap_or_else(|e| {
+                panic!("invalid example manifest {}: {e}", manifest_path.display())
+            })
+        } else {
+            String::new()
+        };
+
+        let out_path = Path::new(&out_dir).join(format!("example_tests_{day:02}.rs"));
+        fs::write(out_path, tests).unwrap();
+    }
+}
+
+fn generate_tests(manifest: &str) -> Result<String, String> {
+    let json = JsonValue::from_str(manifest).map_err(|e| format!("not valid JSON ({e})."))?;
+
+    let cases = json
+        .get::<HashMap<String, JsonValue>>()
+        .ok_or("expected manifest to be an object.")?
+        .get("cases")
+        .ok_or("expected manifest to have key `cases`.")?
+        .get::<Vec<JsonValue>>()
+        .ok_or("expected `cases` to be an array.")?;
+
+    let mut names: Vec<String> = vec![];
+    let mut out = String::new();
+
+    for (i, case) in cases.iter().enumerate() {
+        let test = generate_test(case, &mut names).map_err(|e| format!("`cases[{i}]`: {e}"))?;
+        out.push_str(&test);
+    }
+
+    Ok(out)
+}
+
+fn generate_test(case: &JsonValue, names: &mut Vec<String>) -> Result<String, String> {
+    let case = case
+        .get::<HashMap<String, JsonValue>>()
+        .ok_or("expected case to be an object.")?;
+
+    let part = match case.get("part").and_then(|v| v.get::<f64>()) {
+        Some(1.0) => "part_one",
+        Some(2.0) => "part_two",
+        _ => return Err("expected `part` to be 1 or 2.".into()),
+    };
+
+    let example = match case.get("example") {
+        None => None,
+        Some(v) => Some(to_integer(v).ok_or("expected `example` to be an integer.")?),
+    };
+
+    let function = match case.get("function") {
+        None => part.to_string(),
+        Some(v) => v
+            .get::<String>()
+            .filter(|f| is_identifier(f))
+            .ok_or("expected `function` to be an identifier.")?
+            .clone(),
+    };
+
+    let params = match case.get("params") {
+        None => vec![],
+        Some(v) => v
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `params` to be an array.")?
+            .iter()
+            .map(to_literal)
+            .collect::<Option<Vec<_>>>()
+            .ok_or("expected `params` to contain integers or strings.")?,
+    };
+
+    let expected = match case.get("expected") {
+        Some(JsonValue::Null) => "None".to_string(),
+        Some(v) => format!("Some({})", to_answer(v)?),
+        None => return Err("expected case to have key `expected`.".into()),
+    };
+
+    let label = match case.get("name") {
+        None => "example".to_string(),
+        Some(v) => v
+            .get::<String>()
+            .filter(|n| is_identifier(n))
+            .ok_or("expected `name` to be an identifier.")?
+            .clone(),
+    };
+
+    let mut name = format!("{part}_{label}");
+    let duplicates = names.iter().filter(|n| **n == name).count();
+    names.push(name.clone());
+    if duplicates > 0 {
+        write!(name, "_{}", duplicates + 1).unwrap();
+    }
+
+    let read_input = match example {
+        Some(example) => {
+            format!("advent_of_code::template::read_file_part(\"examples\", DAY, {example})")
+        }
+        None => "advent_of_code::template::read_file(\"examples\", DAY)".to_string(),
+    };
+
+    let args = std::iter::once("&input".to_string())
+        .chain(params)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!(
+        "#[test]
+fn {name}() {{
+    let input = {read_input};
+    let input = advent_of_code::template::parse_example(example_parser!(), &input);
+    let result = {function}({args});
+    advent_of_code::template::assert_answer(result, {expected});
+}}
+"
+    ))
+}
+
+/// Renders an expected answer as a string literal, the answer is compared in its displayed form.
+fn to_answer(value: &JsonValue) -> Result<String, String> {
+    match value {
+        JsonValue::String(s) => Ok(format!("{s:?}")),
+        JsonValue::Number(_) => to_integer(value)
+            .map(|n| format!("\"{n}\""))
+            .ok_or_else(|| {
+                "expected `expected` to be an integer, use a string for answers above 2^53.".into()
+            }),
+        _ => Err("expected `expected` to be null, an integer or a string.".into()),
+    }
+}
+
+fn to_literal(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => Some(format!("{s:?}")),
+        JsonValue::Number(_) => to_integer(value).map(|n| n.to_string()),
+        _ => None,
+    }
+}
+
+/// Integers up to 2^53 are represented exactly by JSON numbers.
+fn to_integer(value: &JsonValue) -> Option<i64> {
+    let n = *value.get::<f64>()?;
+    #[allow(clippy::cast_possible_truncation)]
+    (n.fract() == 0.0 && n.abs() <= 9_007_199_254_740_992.0).then_some(n as i64)
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
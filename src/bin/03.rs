@@ -1,40 +1,57 @@
 advent_of_code::solution!(3);
 
-use regex::Regex;
+use advent_of_code::parse::Parser;
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let re = Regex::new(r"mul\((?<a>\d+),(?<b>\d+)\)").unwrap();
-    let mut sum = 0;
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// The valid instructions in the corrupted memory, everything else is skipped.
+fn parse_instructions(input: &str) -> Vec<Instruction> {
+    let mut parser = Parser::new(input);
+    let mut instructions = Vec::new();
 
-    for cap in re.captures_iter(input) {
-        let a: u32 = cap["a"].parse().unwrap();
-        let b: u32 = cap["b"].parse().unwrap();
-        sum += a * b;
+    while !parser.is_empty() {
+        if let Some(v) = parser.attempt(|p| p.pattern::<u32>("mul({},{})")) {
+            instructions.push(Instruction::Mul(v[0], v[1]));
+        } else if parser.attempt(|p| p.literal("do()")).is_some() {
+            instructions.push(Instruction::Do);
+        } else if parser.attempt(|p| p.literal("don't()")).is_some() {
+            instructions.push(Instruction::Dont);
+        } else {
+            parser.next_char();
+        }
     }
 
+    instructions
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let sum = parse_instructions(input)
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::Mul(a, b) => a * b,
+            _ => 0,
+        })
+        .sum();
+
     Some(sum)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let re_combined =
-        Regex::new(r"(?<do>do\(\))|(?<dont>don't\(\))|mul\((?<a>\d+),(?<b>\d+)\)").unwrap();
-
     let mut sum = 0;
     let mut skip = false;
 
-    for cap in re_combined.captures_iter(input) {
-        if cap.name("do").is_some() {
-            // do()
-            skip = false;
-        } else if cap.name("dont").is_some() {
-            // don't()
-            skip = true;
-        } else {
-            // mul(a,b)
-            let a: u32 = cap["a"].parse().unwrap();
-            let b: u32 = cap["b"].parse().unwrap();
-            if !skip {
-                sum += a * b;
+    for instruction in parse_instructions(input) {
+        match instruction {
+            Instruction::Do => skip = false,
+            Instruction::Dont => skip = true,
+            Instruction::Mul(a, b) => {
+                if !skip {
+                    sum += a * b;
+                }
             }
         }
     }
@@ -46,15 +63,5 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(161));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(48));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,74 +1,29 @@
 advent_of_code::solution!(4);
 
-fn check_xmas(grid: &Vec<Vec<char>>, i: usize, j: usize) -> u32 {
-    let mut xmas_count = 0;
-    let xmas_chars = ['X', 'M', 'A', 'S'];
-    let directions = [
-        (1, 0),   // horizontal right
-        (-1, 0),  // horizontal left
-        (0, 1),   // vertical down
-        (0, -1),  // vertical up
-        (1, 1),   // diagonal down-right
-        (-1, -1), // diagonal up-left
-        (1, -1),  // diagonal down-left
-        (-1, 1),  // diagonal up-right
-    ];
+use advent_of_code::grid::{Grid, Point};
 
-    for &(di, dj) in &directions {
-        if (0..4).all(|k| {
-            let ni = i as isize + k * di;
-            let nj = j as isize + k * dj;
-            ni >= 0
-                && nj >= 0
-                && ni < grid.len() as isize
-                && nj < grid[0].len() as isize
-                && grid[ni as usize][nj as usize] == xmas_chars[k as usize]
-        }) {
-            xmas_count += 1;
-        }
-    }
-
-    xmas_count
+/// Whether `word` is spelled from `start` stepping by `direction`.
+fn spells(grid: &Grid<char>, word: &str, start: Point, direction: Point) -> bool {
+    word.chars()
+        .zip(0..)
+        .all(|(c, k)| grid.get(start + direction * k) == Some(&c))
 }
 
-fn check_mas(grid: &Vec<Vec<char>>, i: usize, j: usize) -> u32 {
-    let mut diag_1_ok = false;
-    let mut diag_2_ok = false;
-
-    let mas_chars = ['M', 'A', 'S'];
-
-    let diag_1 = [(-1, -1, 1, 1), (1, 1, -1, -1)];
-    let diag_2 = [(-1, 1, 1, -1), (1, -1, -1, 1)];
-
-    for &(oi, oj, di, dj) in &diag_1 {
-        if (0..3).all(|k| {
-            let ni = i as isize + oi + di * k;
-            let nj = j as isize + oj + dj * k;
-            ni >= 0
-                && nj >= 0
-                && ni < grid.len() as isize
-                && nj < grid[0].len() as isize
-                && grid[ni as usize][nj as usize] == mas_chars[k as usize]
-        }) {
-            diag_1_ok = true;
-        }
-    }
+fn check_xmas(grid: &Grid<char>, p: Point) -> u32 {
+    Point::ADJACENT
+        .iter()
+        .filter(|&&direction| spells(grid, "XMAS", p, direction))
+        .count() as u32
+}
 
-    for &(oi, oj, di, dj) in &diag_2 {
-        if (0..3).all(|k| {
-            let ni = i as isize + oi + di * k;
-            let nj = j as isize + oj + dj * k;
-            ni >= 0
-                && nj >= 0
-                && ni < grid.len() as isize
-                && nj < grid[0].len() as isize
-                && grid[ni as usize][nj as usize] == mas_chars[k as usize]
-        }) {
-            diag_2_ok = true;
-        }
-    }
+fn check_mas(grid: &Grid<char>, p: Point) -> u32 {
+    // each diagonal through `p` must read MAS in either direction
+    let diagonal_ok = |direction: Point| {
+        spells(grid, "MAS", p - direction, direction)
+            || spells(grid, "MAS", p + direction, -direction)
+    };
 
-    if diag_1_ok && diag_2_ok {
+    if diagonal_ok(Point::new(1, 1)) && diagonal_ok(Point::new(1, -1)) {
         1
     } else {
         0
@@ -76,50 +31,20 @@ fn check_mas(grid: &Vec<Vec<char>>, i: usize, j: usize) -> u32 {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut counter = 0;
-
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+    let grid = Grid::parse(input);
 
-    for row in 0..grid.len() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'X' {
-                counter += check_xmas(&grid, row, col);
-            }
-        }
-    }
-
-    Some(counter)
+    Some(grid.find_all(&'X').map(|p| check_xmas(&grid, p)).sum())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut counter = 0;
-
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-
-    for row in 0..grid.len() {
-        for col in 0..grid[row].len() {
-            if grid[row][col] == 'A' {
-                counter += check_mas(&grid, row, col);
-            }
-        }
-    }
+    let grid = Grid::parse(input);
 
-    Some(counter)
+    Some(grid.find_all(&'A').map(|p| check_mas(&grid, p)).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(18));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(9));
-    }
+    advent_of_code::example_tests!();
 }
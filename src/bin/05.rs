@@ -1,224 +1,63 @@
 advent_of_code::solution!(5);
-use std::collections::HashMap;
-
-fn floyd_warshall(
-    adjacency_matrix: &Vec<Vec<bool>>,
-    nodes_in_order: &Vec<usize>,
-) -> Vec<Vec<bool>> {
-    let n = adjacency_matrix.len();
-
-    let mut paths: Vec<Vec<bool>> = adjacency_matrix.clone();
-    let mut sub_adjacency_matrix = adjacency_matrix.clone();
-
-    for i in 0..n {
-        if !nodes_in_order.contains(&i) {
-            // set the row and column to false
-            for j in 0..n {
-                sub_adjacency_matrix[i][j] = false;
-                sub_adjacency_matrix[j][i] = false;
-            }
-        }
-    }
-    // check if there is a path from i to j
-    for k in 0..n {
-        for i in 0..n {
-            for j in 0..n {
-                paths[i][j] |= sub_adjacency_matrix[i][k] & sub_adjacency_matrix[k][j];
-            }
-        }
-    }
 
-    paths
-}
+use advent_of_code::graph::{Graph, NodeId};
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut node_to_id = HashMap::new();
-    let mut id_to_node = HashMap::new();
-    let mut edges = Vec::new();
-
-    let mut edge_scan = true;
-
-    let mut orders: Vec<Vec<u32>> = Vec::new();
-
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            edge_scan = false;
-            continue;
-        }
-
-        if edge_scan {
-            let (left, right) = line.trim().split_once('|').unwrap();
-
-            let left = left.parse::<u32>().unwrap();
-            let right = right.parse::<u32>().unwrap();
-
-            if !node_to_id.contains_key(&left) {
-                node_to_id.insert(left, node_to_id.len());
-                id_to_node.insert(node_to_id.len() - 1, left);
-            }
-            if !node_to_id.contains_key(&right) {
-                node_to_id.insert(right, node_to_id.len());
-                id_to_node.insert(node_to_id.len() - 1, right);
-            }
-            // add the edge
-            edges.push((node_to_id[&left], node_to_id[&right]));
-        } else {
-            orders.push(
-                line.trim()
-                    .split(',')
-                    .map(|s| s.parse::<u32>().unwrap())
-                    .collect(),
-            );
-        }
-    }
+/// The page ordering rules as a graph with an edge from every page to the pages that must follow it,
+/// and the updates as lists of node ids.
+fn parse_input(input: &str) -> (Graph<u32>, Vec<Vec<NodeId>>) {
+    let (rules, updates) = input.split_once("\n\n").unwrap();
 
-    let n = node_to_id.len();
-    let mut adjacency_matrix: Vec<Vec<bool>> = vec![vec![false; n]; n];
+    let mut graph = Graph::directed();
 
-    for (left, right) in edges {
-        adjacency_matrix[left][right] = true;
+    for line in rules.lines() {
+        let (left, right) = line.trim().split_once('|').unwrap();
+        graph.add_edge(left.parse().unwrap(), right.parse().unwrap());
     }
 
-    // Print matrix with headers
-    // println!("Adjacency Matrix:");
-
-    // // Print column headers
-    // print!("     ");
-    // for j in 0..n {
-    //     print!("{:4} ", id_to_node[&j]);
-    // }
-    // println!();
-
-    // // Print rows with headers
-    // for i in 0..n {
-    //     print!("{:4} ", id_to_node[&i]);
-    //     for j in 0..n {
-    //         print!("{:4} ", if adjacency_matrix[i][j] { "1" } else { "0" });
-    //     }
-    //     println!();
-    // }
-    // println!();
-
-    // prints the paths matrix
-    // println!("Paths Matrix:");
-    // for i in 0..n {
-    //     print!("{:4} ", id_to_node[&i]);
-    //     for j in 0..n {
-    //         print!("{:4} ", if paths[i][j] { "1" } else { "0" });
-    //     }
-    //     println!();
-    // }
-    // println!();
-    // println!("{:?}", paths);
-
-    let mut answer = 0;
+    let updates = updates
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.trim()
+                .split(',')
+                .map(|s| graph.add_node(s.parse().unwrap()))
+                .collect()
+        })
+        .collect();
+
+    (graph, updates)
+}
 
-    for order in orders {
-        let mut legit = true;
-
-        let nodes_in_order = order.iter().map(|x| node_to_id[&x]).collect::<Vec<_>>();
-        let paths = floyd_warshall(&adjacency_matrix, &nodes_in_order);
-
-        for i in 0..order.len() {
-            for j in 0..i {
-                if paths[node_to_id[&order[i]]][node_to_id[&order[j]]] {
-                    legit = false;
-                    break;
-                }
-            }
-            if !legit {
-                break;
-            }
-        }
-        if legit {
-            answer += order[order.len() / 2];
-        }
-    }
+/// An update is in order when no rule requires a later page to come before an earlier one.
+fn is_ordered(graph: &Graph<u32>, update: &[NodeId]) -> bool {
+    (0..update.len()).all(|i| (0..i).all(|j| !graph.has_edge(update[i], update[j])))
+}
 
-    Some(answer)
+fn middle_page(graph: &Graph<u32>, update: &[NodeId]) -> u32 {
+    *graph.name(update[update.len() / 2])
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut node_to_id = HashMap::new();
-    let mut id_to_node = HashMap::new();
-    let mut edges = Vec::new();
-
-    let mut edge_scan = true;
-
-    let mut orders: Vec<Vec<u32>> = Vec::new();
-
-    for line in input.lines() {
-        if line.trim().is_empty() {
-            edge_scan = false;
-            continue;
-        }
-
-        if edge_scan {
-            let (left, right) = line.trim().split_once('|').unwrap();
-
-            let left = left.parse::<u32>().unwrap();
-            let right = right.parse::<u32>().unwrap();
-
-            if !node_to_id.contains_key(&left) {
-                node_to_id.insert(left, node_to_id.len());
-                id_to_node.insert(node_to_id.len() - 1, left);
-            }
-            if !node_to_id.contains_key(&right) {
-                node_to_id.insert(right, node_to_id.len());
-                id_to_node.insert(node_to_id.len() - 1, right);
-            }
-            // add the edge
-            edges.push((node_to_id[&left], node_to_id[&right]));
-        } else {
-            orders.push(
-                line.trim()
-                    .split(',')
-                    .map(|s| s.parse::<u32>().unwrap())
-                    .collect(),
-            );
-        }
-    }
+pub fn part_one(input: &str) -> Option<u32> {
+    let (graph, updates) = parse_input(input);
 
-    let n = node_to_id.len();
-    let mut adjacency_matrix: Vec<Vec<bool>> = vec![vec![false; n]; n];
+    let answer = updates
+        .iter()
+        .filter(|update| is_ordered(&graph, update))
+        .map(|update| middle_page(&graph, update))
+        .sum();
 
-    for (left, right) in edges {
-        adjacency_matrix[left][right] = true;
-    }
+    Some(answer)
+}
 
-    // bubble sort on the sub adjacency matrix
+pub fn part_two(input: &str) -> Option<u32> {
+    let (graph, updates) = parse_input(input);
 
     let mut answer = 0;
 
-    for order in orders {
-        let nodes_in_order = order.iter().map(|x| node_to_id[&x]).collect::<Vec<_>>();
-        let paths = floyd_warshall(&adjacency_matrix, &nodes_in_order);
-
-        let mut legit = true;
-
-        let nodes_in_order = order.iter().map(|x| node_to_id[&x]).collect::<Vec<_>>();
-        let paths = floyd_warshall(&adjacency_matrix, &nodes_in_order);
-
-        for i in 0..order.len() {
-            for j in 0..i {
-                if paths[node_to_id[&order[i]]][node_to_id[&order[j]]] {
-                    legit = false;
-                    break;
-                }
-            }
-            if !legit {
-                break;
-            }
-        }
-        if !legit {
-            let mut sorted_order = order.clone();
-            // sort the nodes in order based on the preference given by the paths matrix
-            sorted_order.sort_by(|a, b| {
-                paths[node_to_id[a]][node_to_id[b]].cmp(&paths[node_to_id[b]][node_to_id[a]])
-            });
-            // println!("{:?}", sorted_order);
-            answer += sorted_order[sorted_order.len() / 2];
-        }
+    for update in updates.iter().filter(|update| !is_ordered(&graph, update)) {
+        // only the rules between the pages of this update apply, the full rule set is cyclic.
+        let sorted = graph.toposort_of(update).ok()?;
+        answer += middle_page(&graph, &sorted);
     }
 
     Some(answer)
@@ -228,15 +67,5 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,234 +1,184 @@
-use std::collections::HashMap;
+use advent_of_code::grid::{Direction, Grid, Point};
+use std::thread;
 
 advent_of_code::solution!(6);
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+fn get_position(grid: &Grid<char>) -> (Point, Direction) {
+    grid.iter()
+        .find_map(|(p, c)| Direction::from_arrow(*c).map(|d| (p, d)))
+        .unwrap()
 }
 
-impl Direction {
-    fn get_offset(&self) -> (i32, i32) {
-        match self {
-            Direction::Up => (-1, 0),
-            Direction::Right => (0, 1),
-            Direction::Down => (1, 0),
-            Direction::Left => (0, -1),
-        }
-    }
+fn check_blocked(grid: &Grid<char>, next: Point) -> bool {
+    grid[next] == '#'
+}
+
+/// For every cell and direction, the cell where the guard stops in front of the next obstacle,
+/// `None` if the guard leaves the grid instead.
+struct JumpTable {
+    jumps: Grid<[Option<Point>; 4]>,
 }
 
-fn get_position(grid: &Vec<Vec<char>>) -> (usize, usize, Direction) {
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            match grid[i][j] {
-                '^' => return (i, j, Direction::Up),
-                '>' => return (i, j, Direction::Right),
-                'v' => return (i, j, Direction::Down),
-                '<' => return (i, j, Direction::Left),
-                _ => continue,
+impl JumpTable {
+    fn new(grid: &Grid<char>) -> Self {
+        let mut jumps = Grid::new(grid.width(), grid.height(), [None; 4]);
+
+        // the jump of a cell continues the jump of the cell in front of it,
+        // so fill each direction starting at the side the guard walks towards.
+        let forward: Vec<Point> = grid.points().collect();
+        for direction in Direction::ALL {
+            let points: Box<dyn Iterator<Item = &Point>> = match direction {
+                Direction::Up | Direction::Left => Box::new(forward.iter()),
+                Direction::Down | Direction::Right => Box::new(forward.iter().rev()),
+            };
+            for &p in points {
+                let next = p.step(direction);
+                jumps[p][direction as usize] = if !grid.contains(next) {
+                    None
+                } else if check_blocked(grid, next) {
+                    Some(p)
+                } else {
+                    jumps[next][direction as usize]
+                };
             }
         }
-    }
-    unreachable!()
-}
 
-fn check_blocked(grid: &Vec<Vec<char>>, next_x: i32, next_y: i32) -> bool {
-    if grid[next_x as usize][next_y as usize] == '#' {
-        return true;
+        Self { jumps }
     }
 
-    false
-}
-
-fn check_inside(grid: &Vec<Vec<char>>, x: i32, y: i32) -> bool {
-    x >= 0 && x < grid.len() as i32 && y >= 0 && y < grid[0].len() as i32
-}
-
-fn populate_path(grid: &Vec<Vec<char>>) -> (u32, Vec<Vec<char>>) {
-    let mut path = grid.clone();
+    /// Where the guard at `p` facing `direction` stops, taking the extra `obstruction` into account.
+    fn stop(&self, p: Point, direction: Direction, obstruction: Point) -> Option<Point> {
+        let jump = self.jumps[p][direction as usize];
 
-    let mut inside = true;
+        let ahead = obstruction - p;
+        let offset = direction.offset();
+        let in_line = match direction {
+            Direction::Up | Direction::Down => ahead.x == 0 && ahead.y.signum() == offset.y,
+            Direction::Left | Direction::Right => ahead.y == 0 && ahead.x.signum() == offset.x,
+        };
+        let blocks = in_line && jump.is_none_or(|j| p.manhattan(obstruction) <= p.manhattan(j));
 
-    while inside {
-        // print the grid
-        // for row in path.iter() {
-        //     println!("{}", row.iter().collect::<String>());
-        // }
-        // println!("{}", "=".repeat(path[0].len()));
+        if blocks {
+            Some(obstruction - offset)
+        } else {
+            jump
+        }
+    }
+}
 
-        let (x, y, direction) = get_position(&path);
-        let (dx, dy) = direction.get_offset();
+/// Walks the guard's route, returning every distinct cell it visits after the start, together
+/// with its position and direction right before it first steps onto the cell.
+fn walk(grid: &Grid<char>) -> Vec<(Point, Point, Direction)> {
+    let (mut position, mut direction) = get_position(grid);
 
-        // println!("x: {}, y: {}, direction: {:?}", x, y, direction);
-        // println!("{}", "=".repeat(path[0].len()));
+    let mut seen = Grid::new(grid.width(), grid.height(), false);
+    seen[position] = true;
+    let mut first_visits = vec![];
 
-        let next_x = x as i32 + dx;
-        let next_y = y as i32 + dy;
+    loop {
+        let next = position.step(direction);
 
-        if !check_inside(&path, next_x, next_y) {
-            inside = false;
-            path[x as usize][y as usize] = 'X';
+        if !grid.contains(next) {
             break;
         }
 
-        if check_blocked(&path, next_x, next_y) {
-            // rotate 90 degrees
-            let new_direction = match direction {
-                Direction::Up => Direction::Right,
-                Direction::Right => Direction::Down,
-                Direction::Down => Direction::Left,
-                Direction::Left => Direction::Up,
-            };
-            // update the direction
-            match new_direction {
-                Direction::Up => path[x as usize][y as usize] = '^',
-                Direction::Right => path[x as usize][y as usize] = '>',
-                Direction::Down => path[x as usize][y as usize] = 'v',
-                Direction::Left => path[x as usize][y as usize] = '<',
-            }
+        if check_blocked(grid, next) {
+            direction = direction.turn_right();
         } else {
-            // mark that we've been here
-            path[x as usize][y as usize] = 'X';
-
-            // go to the next
-            match direction {
-                Direction::Up => path[next_x as usize][next_y as usize] = '^',
-                Direction::Right => path[next_x as usize][next_y as usize] = '>',
-                Direction::Down => path[next_x as usize][next_y as usize] = 'v',
-                Direction::Left => path[next_x as usize][next_y as usize] = '<',
-            }
-        }
-    }
-
-    // count the number of Xs in the path
-    let mut count: u32 = 0;
-    for row in 0..path.len() {
-        for cell in 0..path[row].len() {
-            if path[row][cell] == 'X' {
-                count += 1;
+            if !seen[next] {
+                seen[next] = true;
+                first_visits.push((next, position, direction));
             }
+            position = next;
         }
     }
 
-    (count, path)
+    first_visits
 }
 
-fn check_loop(grid: &Vec<Vec<char>>) -> bool {
-    let mut path = grid.clone();
-    let mut direction_map: HashMap<(usize, usize, Direction), bool> = HashMap::new();
-
-    let mut inside = true;
-    let mut loop_check: bool = false;
-
-    while inside && !loop_check {
-        let (x, y, direction) = get_position(&path);
-        let (dx, dy) = direction.get_offset();
-
-        let next_x = x as i32 + dx;
-        let next_y = y as i32 + dy;
-
-        if !check_inside(&path, next_x, next_y) {
+/// Whether the guard, placed at `position` facing `direction` in front of the new `obstruction`,
+/// ends up in a loop. `visited` holds the turns of earlier checks, marked with their `stamp`.
+fn check_loop(
+    jumps: &JumpTable,
+    obstruction: Point,
+    mut position: Point,
+    mut direction: Direction,
+    visited: &mut Grid<[u32; 4]>,
+    stamp: u32,
+) -> bool {
+    loop {
+        let Some(stop) = jumps.stop(position, direction, obstruction) else {
             return false;
-            // inside = false;
-            // path[x as usize][y as usize] = 'X';
-        }
+        };
 
-        if check_blocked(&path, next_x, next_y) {
-            // record the direction and the obstacle
-            // then we have a loop
-            if direction_map.contains_key(&(x, y, direction.clone())) {
-                // if for this x,y we have this change of directions
-                return true;
-            } else {
-                direction_map.insert((x, y, direction.clone()), true);
-            }
-
-            // rotate 90 degrees
-            let new_direction = match direction {
-                Direction::Up => Direction::Right,
-                Direction::Right => Direction::Down,
-                Direction::Down => Direction::Left,
-                Direction::Left => Direction::Up,
-            };
-
-            // update the direction
-            match new_direction {
-                Direction::Up => path[x as usize][y as usize] = '^',
-                Direction::Right => path[x as usize][y as usize] = '>',
-                Direction::Down => path[x as usize][y as usize] = 'v',
-                Direction::Left => path[x as usize][y as usize] = '<',
-            }
-        } else {
-            // mark that we've been here
-            path[x as usize][y as usize] = 'X';
-
-            // go to the next
-            match direction {
-                Direction::Up => path[next_x as usize][next_y as usize] = '^',
-                Direction::Right => path[next_x as usize][next_y as usize] = '>',
-                Direction::Down => path[next_x as usize][next_y as usize] = 'v',
-                Direction::Left => path[next_x as usize][next_y as usize] = '<',
-            }
+        // stopping at the same place in the same direction twice repeats the route
+        let turn = &mut visited[stop][direction as usize];
+        if *turn == stamp {
+            return true;
         }
-    }
+        *turn = stamp;
 
-    false
+        position = stop;
+        direction = direction.turn_right();
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+    let grid = Grid::parse(input);
 
-    let (count, _) = populate_path(&grid);
+    let first_visits = walk(&grid);
 
-    Some(count)
+    Some(first_visits.len() as u32 + 1)
 }
 
+/// Tries an obstruction on every cell of the route, except the start. Each check starts right in
+/// front of the obstruction, where the route first reaches it, and jumps from turn to turn.
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let mut alt_grid = grid.clone();
-
-    let (_, original_path) = populate_path(&grid);
-
-    let mut count_loop: u32 = 0;
-
-    for i in 0..alt_grid.len() {
-        for j in 0..alt_grid[i].len() {
-            let total_cells = alt_grid.len() * alt_grid[0].len();
-            let current_cell = i * alt_grid[0].len() + j;
-            let percentage = (current_cell as f64 / total_cells as f64 * 100.0) as u32;
-            println!("Progress: {}%", percentage);
-
-            if alt_grid[i][j] == '.' && original_path[i][j] == 'X' {
-                alt_grid[i][j] = '#';
-                if check_loop(&alt_grid) {
-                    // println!("loop found at: {}, {}", i, j);
-                    count_loop += 1;
-                }
-                alt_grid[i][j] = '.';
-            }
-        }
-    }
-
-    Some(count_loop)
+    let grid = Grid::parse(input);
+    let jumps = JumpTable::new(&grid);
+
+    let candidates = walk(&grid);
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+
+    let count_loop = thread::scope(|scope| {
+        let workers: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let jumps = &jumps;
+                let (width, height) = (grid.width(), grid.height());
+                scope.spawn(move || {
+                    let mut visited = Grid::new(width, height, [0; 4]);
+                    (1..)
+                        .zip(chunk)
+                        .filter(|(stamp, (obstruction, position, direction))| {
+                            check_loop(
+                                jumps,
+                                *obstruction,
+                                *position,
+                                *direction,
+                                &mut visited,
+                                *stamp,
+                            )
+                        })
+                        .count()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .sum::<usize>()
+    });
+
+    Some(count_loop as u32)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(41));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,33 +1,19 @@
-use std::process::id;
-
 advent_of_code::solution!(9);
 
-fn find_next_negative_index(repr: &Vec<i32>, prev_index: i32) -> Option<usize> {
-    for i in (prev_index + 1) as usize..repr.len() {
-        if repr[i] < 0 {
-            return Some(i);
-        }
-    }
-    None
+fn find_next_negative_index(repr: &[i32], prev_index: i32) -> Option<usize> {
+    ((prev_index + 1) as usize..repr.len()).find(|&i| repr[i] < 0)
 }
 
-fn find_prev_negative_index(repr: &Vec<i32>, prev_index: i32) -> Option<usize> {
-    for i in (0..(prev_index) as usize).rev() {
-        if repr[i] >= 0 {
-            return Some(i);
-        }
-    }
-    None
+fn find_prev_negative_index(repr: &[i32], prev_index: i32) -> Option<usize> {
+    (0..(prev_index) as usize).rev().find(|&i| repr[i] >= 0)
 }
 
-fn checksum(repr: &Vec<i32>) -> u64 {
-    let mut ans: u64 = 0;
-    for i in 0..repr.len() {
-        if repr[i] >= 0 {
-            ans += repr[i] as u64 * i as u64;
-        }
-    }
-    ans
+fn checksum(repr: &[i32]) -> u64 {
+    repr.iter()
+        .enumerate()
+        .filter(|(_, &id)| id >= 0)
+        .map(|(i, &id)| id as u64 * i as u64)
+        .sum()
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -58,18 +44,12 @@ pub fn part_one(input: &str) -> Option<u64> {
 
     // println!("{:?}", orig_repr);
 
-    let mut front_index = find_next_negative_index(&orig_repr, -1)
-        .or(Some(orig_repr.len() - 1))
-        .unwrap();
-    let mut end_index = find_prev_negative_index(&orig_repr, orig_repr.len() as i32)
-        .or(Some(0))
-        .unwrap();
+    let mut front_index = find_next_negative_index(&orig_repr, -1).unwrap_or(orig_repr.len() - 1);
+    let mut end_index = find_prev_negative_index(&orig_repr, orig_repr.len() as i32).unwrap_or(0);
 
     while front_index < end_index {
         // swap front and end
-        let temp = orig_repr[front_index];
-        orig_repr[front_index] = orig_repr[end_index];
-        orig_repr[end_index] = temp;
+        orig_repr.swap(front_index, end_index);
 
         // find next front and end
         let next_front = find_next_negative_index(&orig_repr, front_index as i32);
@@ -90,11 +70,6 @@ pub fn part_one(input: &str) -> Option<u64> {
 
 ////// Part 2 //////
 
-enum Block {
-    Space(usize, usize),
-    File(usize, usize, i32),
-}
-
 #[derive(Debug, Clone, Copy)]
 struct File {
     start: usize,
@@ -124,23 +99,7 @@ impl ChunkSize for Space {
     }
 }
 
-impl ChunkSize for Block {
-    fn size(&self) -> usize {
-        match self {
-            Block::Space(start, end) | Block::File(start, end, _) => end - start,
-        }
-    }
-}
-
-impl Block {
-    fn debug_print(&self) -> String {
-        match self {
-            Block::Space(start, end) => format!("(-1 {})", end - start),
-            Block::File(start, end, id) => format!("({} {})", id, end - start),
-        }
-    }
-}
-
+#[allow(dead_code)]
 impl Space {
     fn debug_print(&self) -> String {
         format!(
@@ -152,6 +111,7 @@ impl Space {
     }
 }
 
+#[allow(dead_code)]
 impl File {
     fn debug_print(&self) -> String {
         format!(
@@ -164,19 +124,6 @@ impl File {
     }
 }
 
-fn convert_block_to_repr(blocks: &Vec<Block>) -> Vec<i32> {
-    let mut repr: Vec<i32> = Vec::new();
-
-    for block in blocks {
-        match block {
-            Block::Space(start, end) => repr.extend_from_slice(&vec![-1; end - start]),
-            Block::File(start, end, id) => repr.extend_from_slice(&vec![*id; end - start]),
-        }
-    }
-
-    repr
-}
-
 pub fn part_two(input: &str) -> Option<u64> {
     let orig_code = input.trim().to_string();
 
@@ -227,8 +174,6 @@ pub fn part_two(input: &str) -> Option<u64> {
     // done with parsing
 
     for candidate_file in files.iter_mut().rev() {
-        let mut target_found = false;
-
         // println!("Candidate file {:?}", candidate_file);
 
         for target_space_index in 0..spaces.len() {
@@ -285,20 +230,11 @@ pub fn part_two(input: &str) -> Option<u64> {
     // convert files to repr
     let mut repr: Vec<i32> = vec![-1; orig_repr.len()];
     for file in files.iter() {
-        for i in file.start..file.end {
-            repr[i] = file.id;
-        }
+        repr[file.start..file.end].fill(file.id);
     }
     // println!("{:?}", repr);
 
-    // merge the spaces
-    let mut checksum: u64 = 0;
-    for i in 0..repr.len() {
-        if repr[i] >= 0 {
-            checksum += (repr[i] as u64) * (i as u64);
-        }
-    }
-    Some(checksum)
+    Some(checksum(&repr))
     // println!("Checksum: {}", checksum);
 
     // for file in files.iter() {
@@ -314,16 +250,77 @@ pub fn part_two(input: &str) -> Option<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{self, vec_of};
+
+    advent_of_code::example_tests!();
+
+    /// Disk maps of files with 1 to 9 blocks, each followed by up to 9 free blocks.
+    fn disk_maps() -> impl property::Strategy<Value = Vec<(u32, u32)>> {
+        vec_of((1..=9u32, 0..=9u32), 1..=20)
+    }
+
+    fn render(disk_map: &[(u32, u32)]) -> String {
+        disk_map
+            .iter()
+            .map(|(file, free)| format!("{file}{free}"))
+            .collect()
+    }
+
+    fn expand(disk_map: &[(u32, u32)]) -> Vec<i32> {
+        let mut disk = vec![];
+        for (id, &(file, free)) in disk_map.iter().enumerate() {
+            disk.extend(vec![id as i32; file as usize]);
+            disk.extend(vec![-1; free as usize]);
+        }
+        disk
+    }
+
+    /// Moves single blocks from the end of the disk into the leftmost free block.
+    fn naive_compact_blocks(disk_map: &[(u32, u32)]) -> u64 {
+        let mut disk = expand(disk_map);
+        while let Some(free) = disk.iter().position(|&b| b < 0) {
+            let last = disk.iter().rposition(|&b| b >= 0).unwrap();
+            if last < free {
+                break;
+            }
+            disk.swap(free, last);
+        }
+        checksum(&disk)
+    }
+
+    /// Moves every file, highest id first, into the leftmost run of free blocks that fits it.
+    fn naive_compact_files(disk_map: &[(u32, u32)]) -> u64 {
+        let mut disk = expand(disk_map);
+        for id in (0..disk_map.len() as i32).rev() {
+            let start = disk.iter().position(|&b| b == id).unwrap();
+            let len = disk_map[id as usize].0 as usize;
+
+            let target =
+                (0..start).find(|&i| i + len <= start && disk[i..i + len].iter().all(|&b| b < 0));
+
+            if let Some(target) = target {
+                disk[target..target + len].fill(id);
+                disk[start..start + len].fill(-1);
+            }
+        }
+        checksum(&disk)
+    }
 
     #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1928));
+    fn part_one_matches_naive_compaction() {
+        property::check_equivalent(
+            &disk_maps(),
+            |disk_map| Some(naive_compact_blocks(disk_map)),
+            |disk_map| part_one(&render(disk_map)),
+        );
     }
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2858));
+    fn part_two_matches_naive_compaction() {
+        property::check_equivalent(
+            &disk_maps(),
+            |disk_map| Some(naive_compact_files(disk_map)),
+            |disk_map| part_two(&render(disk_map)),
+        );
     }
 }
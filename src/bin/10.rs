@@ -1,156 +1,59 @@
 advent_of_code::solution!(10);
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap() as i32)
-                .collect()
-        })
-        .collect()
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::pathfinding;
+use std::collections::VecDeque;
+
+fn get_neighbors(grid: &Grid<u8>, p: Point) -> impl Iterator<Item = Point> + '_ {
+    let height = grid[p];
+    grid.neighbors4(p)
+        .filter(move |n| height != 9 && grid[*n] == height + 1)
 }
 
-fn get_neighbors(grid: &Vec<Vec<i32>>, i: usize, j: usize) -> Vec<(usize, usize)> {
-    let mut neighbors = Vec::new();
-
-    if grid[i][j] == 9 {
-        return neighbors;
-    }
-
-    if i > 0 && (grid[i - 1][j] - grid[i][j] == 1) {
-        neighbors.push((i - 1, j));
-    }
-    if i < grid.len() - 1 && (grid[i + 1][j] - grid[i][j] == 1) {
-        neighbors.push((i + 1, j));
-    }
-    if j > 0 && (grid[i][j - 1] - grid[i][j] == 1) {
-        neighbors.push((i, j - 1));
-    }
-    if j < grid[0].len() - 1 && (grid[i][j + 1] - grid[i][j] == 1) {
-        neighbors.push((i, j + 1));
-    }
+fn count_high_points(grid: &Grid<u8>, trailhead: Point) -> u32 {
+    let reachable = pathfinding::bfs_distances(trailhead, |p| get_neighbors(grid, *p));
 
-    neighbors
-}
-
-fn do_bfs(grid: &Vec<Vec<i32>>, i: usize, j: usize) -> Vec<Vec<bool>> {
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut visited: Vec<Vec<bool>> = vec![vec![false; n]; m];
-    // let mut visited_peaks = HashSet::new();
-
-    let mut queue = Vec::new();
-    queue.push((i, j));
-
-    while !queue.is_empty() {
-        let (i, j) = queue.remove(0);
-        if visited[i][j] {
-            continue;
-        }
-        visited[i][j] = true;
-
-        for (ni, nj) in get_neighbors(grid, i, j) {
-            if !visited[ni][nj] {
-                queue.push((ni, nj));
-            }
-        }
-    }
-
-    visited
-}
-
-fn count_high_points(grid: &Vec<Vec<i32>>, visited: &Vec<Vec<bool>>) -> u32 {
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut ans: u32 = 0;
-
-    for i in 0..m {
-        for j in 0..n {
-            if visited[i][j] && grid[i][j] == 9 {
-                ans += 1;
-            }
-        }
-    }
-
-    ans
+    reachable.keys().filter(|p| grid[**p] == 9).count() as u32
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid = parse_input(input);
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut ans: u32 = 0;
-
-    for i in 0..m {
-        for j in 0..n {
-            if grid[i][j] == 0 {
-                let visited: Vec<Vec<bool>> = do_bfs(&grid, i, j);
-                ans += count_high_points(&grid, &visited);
-            }
-        }
-    }
+    let grid = Grid::parse_digits(input);
+
+    let ans = grid
+        .find_all(&0)
+        .map(|trailhead| count_high_points(&grid, trailhead))
+        .sum();
 
     Some(ans)
 }
 
-fn calculate_unique_paths_via_bfs(grid: &Vec<Vec<i32>>, i: usize, j: usize) -> Vec<Vec<u32>> {
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut visited: Vec<Vec<u32>> = vec![vec![0; n]; m];
+fn calculate_unique_paths_via_bfs(grid: &Grid<u8>, start: Point) -> Grid<u32> {
+    let mut visited = Grid::new(grid.width(), grid.height(), 0);
 
-    let mut queue = Vec::new();
-    queue.push((i, j));
+    let mut queue = VecDeque::from([start]);
 
-    while !queue.is_empty() {
-        let (i, j) = queue.remove(0);
+    while let Some(p) = queue.pop_front() {
+        visited[p] += 1;
 
-        visited[i][j] = visited[i][j] + 1;
-
-        for (ni, nj) in get_neighbors(grid, i, j) {
-            queue.push((ni, nj));
-        }
+        queue.extend(get_neighbors(grid, p));
     }
 
     visited
 }
 
-fn count_unique_paths(grid: &Vec<Vec<i32>>, visited: &Vec<Vec<u32>>) -> u32 {
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut ans: u32 = 0;
-
-    for i in 0..m {
-        for j in 0..n {
-            if visited[i][j] > 0 && grid[i][j] == 9 {
-                ans += visited[i][j];
-            }
-        }
-    }
-
-    ans
+fn count_unique_paths(grid: &Grid<u8>, visited: &Grid<u32>) -> u32 {
+    grid.find_all(&9).map(|p| visited[p]).sum()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid = parse_input(input);
-    let m = grid.len();
-    let n = grid[0].len();
-
-    let mut ans: u32 = 0;
-
-    for i in 0..m {
-        for j in 0..n {
-            if grid[i][j] == 0 {
-                let visited: Vec<Vec<u32>> = calculate_unique_paths_via_bfs(&grid, i, j);
-                ans += count_unique_paths(&grid, &visited);
-            }
-        }
-    }
+    let grid = Grid::parse_digits(input);
+
+    let ans = grid
+        .find_all(&0)
+        .map(|trailhead| {
+            count_unique_paths(&grid, &calculate_unique_paths_via_bfs(&grid, trailhead))
+        })
+        .sum();
 
     Some(ans)
 }
@@ -159,15 +62,5 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(36));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(81));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,28 +1,28 @@
+use advent_of_code::log;
 use std::collections::HashMap;
 
 advent_of_code::solution!(11);
 
 fn parse_input(input: &str) -> Vec<u64> {
     input
-        .trim()
         .split_whitespace()
         .map(|num| num.parse::<u64>().unwrap())
         .collect()
 }
 
-fn blink(stones: &Vec<u64>) -> Vec<u64> {
+fn blink(stones: &[u64]) -> Vec<u64> {
     let mut new_stones = Vec::new();
 
-    for i in 0..stones.len() {
-        if stones[i] == 0 {
+    for &stone in stones {
+        if stone == 0 {
             new_stones.push(1);
-        } else if stones[i].to_string().len() % 2 == 0 {
-            let num_str = stones[i].to_string();
+        } else if stone.to_string().len().is_multiple_of(2) {
+            let num_str = stone.to_string();
             let (left, right) = num_str.split_at(num_str.len() / 2);
             new_stones.push(left.parse::<u64>().unwrap());
             new_stones.push(right.parse::<u64>().unwrap());
         } else {
-            new_stones.push(stones[i] * 2024);
+            new_stones.push(stone * 2024);
         }
     }
 
@@ -32,7 +32,7 @@ fn blink(stones: &Vec<u64>) -> Vec<u64> {
 pub fn part_one(input: &str) -> Option<u32> {
     let mut stones = parse_input(input);
 
-    for i in 0..25 {
+    for _ in 0..25 {
         stones = blink(&stones);
     }
 
@@ -69,7 +69,7 @@ pub fn part_two(input: &str) -> Option<u64> {
         *stones.entry(stone).or_insert(0) += 1;
     }
 
-    println!("{:?}", stones);
+    log::debug!("initial stones: {stones:?}");
 
     for _ in 0..75 {
         stones = fast_blink(&stones);
@@ -83,16 +83,31 @@ pub fn part_two(input: &str) -> Option<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::{self, vec_of};
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(55312));
-    }
+    advent_of_code::example_tests!();
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(239321955280205));
+    fn fast_blink_matches_blink() {
+        property::check_equivalent(
+            &(vec_of(0..=1_000_000u64, 1..=6), 0..=15usize),
+            |(stones, blinks)| {
+                let mut stones = stones.clone();
+                for _ in 0..*blinks {
+                    stones = blink(&stones);
+                }
+                stones.len() as u64
+            },
+            |(stones, blinks)| {
+                let mut counts = HashMap::new();
+                for stone in stones {
+                    *counts.entry(*stone).or_insert(0) += 1;
+                }
+                for _ in 0..*blinks {
+                    counts = fast_blink(&counts);
+                }
+                counts.values().sum::<u64>()
+            },
+        );
     }
 }
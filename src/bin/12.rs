@@ -1,62 +1,35 @@
-use std::collections::HashSet;
+use advent_of_code::grid::{Grid, Point};
 use std::collections::VecDeque;
 
 advent_of_code::solution!(12);
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
-}
-
-fn get_neighbors(grid: &Vec<Vec<char>>, i: usize, j: usize) -> Vec<(usize, usize)> {
-    let mut neighbors = Vec::new();
-    let m = grid.len();
-    let n = grid[0].len();
-
-    if i > 0 && grid[i - 1][j] == grid[i][j] {
-        neighbors.push((i - 1, j));
-    }
-    if i < m - 1 && grid[i + 1][j] == grid[i][j] {
-        neighbors.push((i + 1, j));
-    }
-    if j > 0 && grid[i][j - 1] == grid[i][j] {
-        neighbors.push((i, j - 1));
-    }
-    if j < n - 1 && grid[i][j + 1] == grid[i][j] {
-        neighbors.push((i, j + 1));
-    }
-
-    neighbors
+fn get_neighbors(grid: &Grid<char>, p: Point) -> impl Iterator<Item = Point> + '_ {
+    grid.neighbors4(p).filter(move |n| grid[*n] == grid[p])
 }
 
 fn get_regional_values(
-    grid: &Vec<Vec<char>>,
-    visited: &mut Vec<Vec<i32>>,
-    i: usize,
-    j: usize,
+    grid: &Grid<char>,
+    visited: &mut Grid<i32>,
+    start: Point,
     id: i32,
 ) -> (u32, u32) {
     let mut area = 0;
     let mut perimeter = 0;
 
-    let mut queue = VecDeque::new();
-    queue.push_back((i, j));
+    let mut queue = VecDeque::from([start]);
 
-    while let Some((i, j)) = queue.pop_front() {
-        if visited[i][j] >= 0 {
+    while let Some(p) = queue.pop_front() {
+        if visited[p] >= 0 {
             continue;
         }
 
-        visited[i][j] = id;
+        visited[p] = id;
         area += 1;
 
-        let all_neighbors = get_neighbors(grid, i, j);
+        let all_neighbors: Vec<Point> = get_neighbors(grid, p).collect();
         perimeter += 4 - all_neighbors.len() as u32;
 
-        for (ni, nj) in all_neighbors {
-            if visited[ni][nj] == -1 {
-                queue.push_back((ni, nj));
-            }
-        }
+        queue.extend(all_neighbors.into_iter().filter(|n| visited[*n] == -1));
     }
 
     (area, perimeter)
@@ -65,16 +38,14 @@ fn get_regional_values(
 #[derive(Debug)]
 struct Region {
     id: i32,
-    plant: char,
     area: u32,
     perimeter: u32,
 }
 
 impl Region {
-    fn new(id: i32, plant: char, area: u32, perimeter: Option<u32>) -> Self {
+    fn new(id: i32, area: u32, perimeter: Option<u32>) -> Self {
         Self {
             id,
-            plant,
             area,
             perimeter: perimeter.unwrap_or(0),
         }
@@ -89,68 +60,42 @@ impl Region {
     }
 }
 
-fn is_corner(padded_visited: &Vec<Vec<i32>>, id: i32, i: i32, j: i32) -> bool {
-    let m = padded_visited.len() as i32;
-    let n = padded_visited[0].len() as i32;
-
-    let mut same_id = 0;
-
-    if i - 1 >= 0 && j - 1 >= 0 && padded_visited[(i - 1) as usize][(j - 1) as usize] == id {
-        same_id += 1;
-    }
-    if i - 1 >= 0 && j + 1 < n && padded_visited[(i - 1) as usize][(j + 1) as usize] == id {
-        same_id += 1;
-    }
-    if i + 1 < m && j - 1 >= 0 && padded_visited[(i + 1) as usize][(j - 1) as usize] == id {
-        same_id += 1;
-    }
-    if i + 1 < m && j + 1 < n && padded_visited[(i + 1) as usize][(j + 1) as usize] == id {
-        same_id += 1;
-    }
+fn is_corner(padded_visited: &Grid<i32>, id: i32, p: Point) -> bool {
+    let same_id = Point::DIAGONAL
+        .iter()
+        .filter(|&&d| padded_visited.get(p + d) == Some(&id))
+        .count();
 
     same_id == 1 || same_id == 3
 }
 
-fn get_corners(visited: &Vec<Vec<i32>>, id: i32) -> u32 {
-    let m = visited.len() as i32;
-    let n = visited[0].len() as i32;
-
-    let mut padded_visited = vec![vec![-1; (2 * n + 1) as usize]; (2 * m + 1) as usize];
+fn get_corners(visited: &Grid<i32>, id: i32) -> u32 {
+    let mut padded_visited = Grid::new(2 * visited.width() + 1, 2 * visited.height() + 1, -1);
 
-    for i in 0..m as usize {
-        for j in 0..n as usize {
-            padded_visited[2 * i + 1][2 * j + 1] = visited[i][j];
-        }
-    }
-
-    let mut corners = 0;
-
-    for i in (0..padded_visited.len()).step_by(2) {
-        for j in (0..padded_visited[0].len()).step_by(2) {
-            if is_corner(&padded_visited, id, i as i32, j as i32) {
-                corners += 1;
-            }
-        }
+    for (p, region) in visited.iter() {
+        padded_visited[p * 2 + Point::new(1, 1)] = *region;
     }
 
-    corners
+    padded_visited
+        .points()
+        .filter(|p| p.x % 2 == 0 && p.y % 2 == 0 && is_corner(&padded_visited, id, *p))
+        .count() as u32
 }
+
 pub fn part_one(input: &str) -> Option<u64> {
-    let grid = parse_input(input);
+    let grid = Grid::parse(input);
 
-    let mut visited = vec![vec![-1; grid[0].len()]; grid.len()];
+    let mut visited = Grid::new(grid.width(), grid.height(), -1);
 
     let mut cost: u64 = 0;
 
     let mut id = 0;
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if visited[i][j] == -1 {
-                let (area, parameter) = get_regional_values(&grid, &mut visited, i, j, id);
-                cost += area as u64 * parameter as u64;
-                id += 1;
-            }
+    for p in grid.points() {
+        if visited[p] == -1 {
+            let (area, parameter) = get_regional_values(&grid, &mut visited, p, id);
+            cost += area as u64 * parameter as u64;
+            id += 1;
         }
     }
 
@@ -158,55 +103,31 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let grid = parse_input(input);
+    let grid = Grid::parse(input);
 
-    let mut visited = vec![vec![-1; grid[0].len()]; grid.len()];
-
-    let mut cost: u64 = 0;
+    let mut visited = Grid::new(grid.width(), grid.height(), -1);
 
     let mut id = 0;
     let mut regions: Vec<Region> = Vec::new();
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if visited[i][j] == -1 {
-                let (area, _) = get_regional_values(&grid, &mut visited, i, j, id);
-                let region = Region::new(id, grid[i][j], area, None);
-                regions.push(region);
-                id += 1;
-            }
+    for p in grid.points() {
+        if visited[p] == -1 {
+            let (area, _) = get_regional_values(&grid, &mut visited, p, id);
+            regions.push(Region::new(id, area, None));
+            id += 1;
         }
     }
 
     for region in &mut regions {
-        // println!("---- Plant {} -------", region.plant);
         region.set_perimeter(get_corners(&visited, region.id));
     }
 
-    for region in &regions {
-        // println!("{:?}", region);
-    }
-
-    for i in 0..regions.len() {
-        cost += regions[i].cost();
-    }
-
-    Some(cost)
+    Some(regions.iter().map(Region::cost).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1930));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1206));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,5 +1,7 @@
 advent_of_code::solution!(13);
 
+use advent_of_code::parse::{ParseError, Parser};
+
 #[derive(Clone)]
 pub struct Coordinates {
     x: u64,
@@ -12,23 +14,6 @@ impl Coordinates {
     }
 }
 
-#[derive(Clone)]
-pub struct Location {
-    coordinates: Coordinates,
-    num_a_token: u64,
-    num_b_token: u64,
-}
-
-impl Location {
-    pub fn new(coordinates: Coordinates, num_a_token: u64, num_b_token: u64) -> Self {
-        Self {
-            coordinates,
-            num_a_token,
-            num_b_token,
-        }
-    }
-}
-
 fn solve_for_one_machine(a: Coordinates, b: Coordinates, prize: Coordinates) -> u64 {
     let mut best_answer: Vec<u64> = Vec::new();
 
@@ -49,78 +34,33 @@ fn solve_for_one_machine(a: Coordinates, b: Coordinates, prize: Coordinates) ->
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut answer: u32 = 0;
-
-    let mut machine_number = 0;
-
-    input
-        .split("\n\n") // Split on double newlines to get groups
-        .filter(|group| !group.is_empty())
-        .for_each(|group| {
-            let mut lines = group.lines();
-
-            // Parse Button A
-            let button_a = lines.next().unwrap();
-            let (a_x, a_y) = parse_button(button_a, "Button A:");
-
-            // Parse Button B
-            let button_b = lines.next().unwrap();
-            let (b_x, b_y) = parse_button(button_b, "Button B:");
-
-            // Parse Prize
-            let prize = lines.next().unwrap();
-            let (prize_x, prize_y) = parse_prize(prize);
-
-            // solve for the prize now !!
-            let tokens_for_this_machine = solve_for_one_machine(
-                Coordinates::new(a_x, a_y),
-                Coordinates::new(b_x, b_y),
-                Coordinates::new(prize_x, prize_y),
-            );
-
-            machine_number += 1;
-
-            answer += tokens_for_this_machine as u32;
-        });
-
-    Some(answer)
+struct Machine {
+    a: Coordinates,
+    b: Coordinates,
+    prize: Coordinates,
 }
 
-fn parse_button(line: &str, prefix: &str) -> (u64, u64) {
-    let coords = line.strip_prefix(prefix).unwrap().trim();
-    let mut parts = coords.split(", ");
-    let x = parts
-        .next()
-        .unwrap()
-        .trim_start_matches("X+")
-        .parse()
-        .unwrap();
-    let y = parts
-        .next()
-        .unwrap()
-        .trim_start_matches("Y+")
-        .parse()
-        .unwrap();
-    (x, y)
+fn parse_input(input: &str) -> Result<Vec<Machine>, ParseError> {
+    Parser::new(input).sections(|p| {
+        let v =
+            p.pattern::<u64>("Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}")?;
+        Ok(Machine {
+            a: Coordinates::new(v[0], v[1]),
+            b: Coordinates::new(v[2], v[3]),
+            prize: Coordinates::new(v[4], v[5]),
+        })
+    })
 }
 
-fn parse_prize(line: &str) -> (u64, u64) {
-    let coords = line.strip_prefix("Prize:").unwrap().trim();
-    let mut parts = coords.split(", ");
-    let x = parts
-        .next()
-        .unwrap()
-        .trim_start_matches("X=")
-        .parse()
-        .unwrap();
-    let y = parts
-        .next()
-        .unwrap()
-        .trim_start_matches("Y=")
-        .parse()
-        .unwrap();
-    (x, y)
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let machines = parse_input(input)?;
+
+    let answer = machines
+        .into_iter()
+        .map(|machine| solve_for_one_machine(machine.a, machine.b, machine.prize) as u32)
+        .sum();
+
+    Ok(answer)
 }
 
 fn manual_solve_linear_equation(a: Coordinates, b: Coordinates, prize: Coordinates) -> u64 {
@@ -140,67 +80,57 @@ fn manual_solve_linear_equation(a: Coordinates, b: Coordinates, prize: Coordinat
     // }
 
     if ax * a + bx * b == px && ay * a + by * b == py {
-        return (3 * a + b) as u64;
+        (3 * a + b) as u64
     } else {
-        return 0;
+        0
     }
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut answer: u64 = 0;
-
-    let mut machine_number = 0;
-
-    input
-        .split("\n\n") // Split on double newlines to get groups
-        .filter(|group| !group.is_empty())
-        .for_each(|group| {
-            let mut lines = group.lines();
-
-            // Parse Button A
-            let button_a = lines.next().unwrap();
-            let (a_x, a_y) = parse_button(button_a, "Button A:");
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let machines = parse_input(input)?;
 
-            // Parse Button B
-            let button_b = lines.next().unwrap();
-            let (b_x, b_y) = parse_button(button_b, "Button B:");
-
-            // Parse Prize
-            let prize = lines.next().unwrap();
-            let (mut prize_x, mut prize_y) = parse_prize(prize);
-
-            prize_x = prize_x + 10000000000000;
-            prize_y = prize_y + 10000000000000;
-
-            // solve for the prize now !!
-            let tokens_for_this_machine = manual_solve_linear_equation(
-                Coordinates::new(a_x, a_y),
-                Coordinates::new(b_x, b_y),
-                Coordinates::new(prize_x, prize_y),
+    let answer = machines
+        .into_iter()
+        .map(|machine| {
+            let prize = Coordinates::new(
+                machine.prize.x + 10000000000000,
+                machine.prize.y + 10000000000000,
             );
+            manual_solve_linear_equation(machine.a, machine.b, prize)
+        })
+        .sum();
 
-            machine_number += 1;
-
-            answer += tokens_for_this_machine;
-        });
-
-    println!("{}", answer);
-    Some(answer)
+    Ok(answer)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(480));
-    }
+    advent_of_code::example_tests!();
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+    fn linear_solve_matches_brute_force() {
+        // machines are built from a known number of presses, so both solvers have a solution within 100 presses.
+        let button = (1..=99u64, 1..=99u64);
+        let presses = (0..=99u64, 0..=99u64);
+
+        property::check(
+            &(button.clone(), button, presses),
+            |&((ax, ay), (bx, by), (i, j))| {
+                // NOTE: the linear solver expects linearly independent buttons.
+                if ax * by == ay * bx {
+                    return true;
+                }
+
+                let prize = || Coordinates::new(ax * i + bx * j, ay * i + by * j);
+                let a = || Coordinates::new(ax, ay);
+                let b = || Coordinates::new(bx, by);
+
+                solve_for_one_machine(a(), b(), prize())
+                    == manual_solve_linear_equation(a(), b(), prize())
+            },
+        );
     }
 }
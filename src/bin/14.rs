@@ -1,10 +1,15 @@
 advent_of_code::solution!(14);
 
-use colored::*;
-use regex::Regex;
-use std::collections::HashMap;
-use std::io;
-use std::io::Write;
+use advent_of_code::export;
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::image::{self, ContactSheets, Image};
+use advent_of_code::parse::{ParseError, Parser};
+use advent_of_code::visualize::{Animation, Frame};
+use colored::Color;
+use std::cmp::Reverse;
+use std::env;
+use std::error::Error;
+use std::str::FromStr;
 
 #[derive(Clone)]
 struct Robot {
@@ -19,23 +24,42 @@ impl Robot {
         Self { x, y, vx, vy }
     }
 
-    fn step(&mut self, multiplier: i32, max_x: u32, max_y: u32) {
-        let new_x = (self.x as i32 + self.vx * multiplier).rem_euclid(max_x as i32 + 1);
-        let new_y = (self.y as i32 + self.vy * multiplier).rem_euclid(max_y as i32 + 1);
-        self.x = new_x as u32;
-        self.y = new_y as u32;
+    /// The tile after `seconds` on a `width` x `height` grid.
+    fn position_at(&self, seconds: u32, width: u32, height: u32) -> (usize, usize) {
+        let x = (i64::from(self.x) + i64::from(self.vx) * i64::from(seconds))
+            .rem_euclid(i64::from(width));
+        let y = (i64::from(self.y) + i64::from(self.vy) * i64::from(seconds))
+            .rem_euclid(i64::from(height));
+        (x as usize, y as usize)
     }
 }
 
-fn parse_line(line: &str) -> Robot {
-    let re = Regex::new(r"p=(?<x>\d+),(?<y>\d+) v=(?<vx>-?\d+),(?<vy>-?\d+)").unwrap();
-    let cap = re.captures(line).unwrap();
-    Robot::new(
-        cap["x"].parse().unwrap(),
-        cap["y"].parse().unwrap(),
-        cap["vx"].parse().unwrap(),
-        cap["vy"].parse().unwrap(),
-    )
+/// The robots moved to their tiles after `seconds` on a `width` x `height` grid.
+fn robots_after(robots: &[Robot], seconds: u32, width: u32, height: u32) -> Vec<Robot> {
+    robots
+        .iter()
+        .map(|robot| {
+            let (x, y) = robot.position_at(seconds, width, height);
+            Robot::new(x as u32, y as u32, robot.vx, robot.vy)
+        })
+        .collect()
+}
+
+// problem constraints
+const WIDTH: u32 = 101;
+const HEIGHT: u32 = 103;
+
+fn parse_input(input: &str) -> Result<Vec<Robot>, ParseError> {
+    Parser::new(input).lines(|p| {
+        p.literal("p=")?;
+        let x = p.integer()?;
+        p.literal(",")?;
+        let y = p.integer()?;
+        let [vx, vy] = p.pattern(" v={},{}")?[..] else {
+            unreachable!()
+        };
+        Ok(Robot::new(x, y, vx, vy))
+    })
 }
 
 fn calculate_num_robots_in_quadrants(
@@ -70,121 +94,255 @@ fn calculate_num_robots_in_quadrants(
     (q1, q2, q3, q4)
 }
 
-fn print_robots_on_grid(robots: &[Robot], max_x: u32, max_y: u32) {
-    let mut robots_loc = HashMap::new();
-
+/// The number of robots on every tile, the middle row and column are drawn as `+`.
+fn robots_frame(robots: &[Robot], max_x: u32, max_y: u32) -> Frame {
+    let mut counts = Grid::new(max_x as usize + 1, max_y as usize + 1, 0u32);
     for robot in robots {
-        *robots_loc.entry((robot.x, robot.y)).or_insert(0) += 1;
+        counts[Point::new(robot.x as i32, robot.y as i32)] += 1;
     }
 
-    for y in 0..max_y {
-        for x in 0..max_x {
-            if x == max_x / 2 || y == max_y / 2 {
-                print!("+");
-            } else {
-                if robots_loc.contains_key(&(x, y)) {
-                    print!("{}", robots_loc[&(x, y)]);
-                } else {
-                    print!(".");
-                }
-            }
-        }
-        println!();
-    }
+    Frame::from_grid(&counts, |p, count| match count {
+        0 if p.x as u32 == max_x / 2 || p.y as u32 == max_y / 2 => '+',
+        0 => '.',
+        n => char::from_digit(*n, 10).unwrap_or('*'),
+    })
+    .highlight(counts.positions(|count| *count > 0), Color::Green)
 }
 
-fn pretty_print_robots_on_grid(
-    robots: &[Robot],
-    max_x: u32,
-    max_y: u32,
-    time: i32,
-    file_name: &str,
-) {
-    let mut grid = vec![vec!['.'; max_x as usize + 1]; max_y as usize + 1];
-
+/// One white pixel per robot.
+fn robots_image(robots: &[Robot], max_x: u32, max_y: u32) -> Image {
+    let mut image = Image::new(max_x as usize + 1, max_y as usize + 1, image::BLACK);
     for robot in robots {
-        grid[robot.y as usize][robot.x as usize] = '#';
-    }
-
-    let mut file = std::fs::OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(file_name)
-        .unwrap();
-
-    writeln!(file, "{}", time).unwrap();
-    for row in grid {
-        writeln!(file, "{}", row.iter().collect::<String>()).unwrap();
+        image.set(robot.x as usize, robot.y as usize, image::WHITE);
     }
+    image
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut robots = input
-        .lines()
-        .map(|line| parse_line(line))
-        .collect::<Vec<_>>();
-
-    let original_robots = robots.clone();
+/// Safety factor after 100 seconds on a `width` x `height` grid.
+fn safety_factor(input: &str, width: u32, height: u32) -> Result<u32, ParseError> {
+    let robots = robots_after(&parse_input(input)?, 100, width, height);
 
-    // problem constraints
-    let max_x = 101 - 1;
-    let max_y = 103 - 1;
+    let max_x = width - 1;
+    let max_y = height - 1;
 
-    // test-case constraints
-    // let max_x = 11 - 1;
-    // let max_y = 7 - 1;
+    let (q1, q2, q3, q4) = calculate_num_robots_in_quadrants(&robots, max_x, max_y);
 
-    robots
-        .iter_mut()
-        .for_each(|robot| robot.step(100, max_x, max_y));
+    let mut animation = Animation::new();
+    animation.record(|| robots_frame(&robots, max_x, max_y).with_caption("after 100 seconds"));
+    animation.play();
 
-    let mut answer = 0;
+    Ok(q1 * q2 * q3 * q4)
+}
 
-    let (q1, q2, q3, q4) = calculate_num_robots_in_quadrants(&robots, max_x, max_y);
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    safety_factor(input, WIDTH, HEIGHT)
+}
 
-    answer = q1 * q2 * q3 * q4;
+/// Signals that tell the arrangement with the Christmas tree apart from the noise before and after.
+///
+/// The robots repeat their positions every `width * height` seconds (the sides are coprime),
+/// so the brute force detectors look at every second of one period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Detector {
+    /// The robots are closest together, measured by the variance of their positions.
+    Variance,
+    /// The first arrangement where no two robots share a tile.
+    NoOverlap,
+    /// The longest horizontal run of robots, e.g. the frame around the tree. Grids up to 128 wide.
+    LongestRun,
+    /// The x positions repeat every `width` and the y positions every `height` seconds. The
+    /// seconds with the lowest variance in x and y are combined with the chinese remainder theorem.
+    Crt,
+}
 
-    print_robots_on_grid(&robots, max_x, max_y);
+impl FromStr for Detector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "variance" => Ok(Detector::Variance),
+            "no_overlap" => Ok(Detector::NoOverlap),
+            "longest_run" => Ok(Detector::LongestRun),
+            "crt" => Ok(Detector::Crt),
+            _ => Err(format!(
+                "unknown detector {s:?}, expected variance, no_overlap, longest_run or crt"
+            )),
+        }
+    }
+}
 
-    Some(answer)
+impl Detector {
+    /// Seconds until the robots show the tree, within one period.
+    fn find(self, robots: &[Robot], width: u32, height: u32) -> Option<u32> {
+        let period = width * height;
+        let positions = |seconds| {
+            robots
+                .iter()
+                .map(move |r| r.position_at(seconds, width, height))
+        };
+
+        match self {
+            Detector::Variance => (0..period).min_by_key(|&seconds| {
+                variance(positions(seconds).map(|(x, _)| x))
+                    + variance(positions(seconds).map(|(_, y)| y))
+            }),
+            Detector::NoOverlap => {
+                // the second a tile was last taken, so the tiles never need clearing.
+                let mut taken = vec![u32::MAX; period as usize];
+                (0..period).find(|&seconds| {
+                    positions(seconds).all(|(x, y)| {
+                        let tile = &mut taken[y * width as usize + x];
+                        std::mem::replace(tile, seconds) != seconds
+                    })
+                })
+            }
+            // rows as bit masks, for grids up to 128 tiles wide.
+            Detector::LongestRun if width <= u128::BITS => (0..period).max_by_key(|&seconds| {
+                let mut rows = vec![0u128; height as usize];
+                positions(seconds).for_each(|(x, y)| rows[y] |= 1 << x);
+                let longest = rows
+                    .into_iter()
+                    .map(|mut row| {
+                        // every shift shortens all runs by one.
+                        let mut len = 0;
+                        while row != 0 {
+                            row &= row << 1;
+                            len += 1;
+                        }
+                        len
+                    })
+                    .max();
+                // the first of equally long runs.
+                (longest, Reverse(seconds))
+            }),
+            Detector::LongestRun => None,
+            Detector::Crt => {
+                let x_seconds =
+                    (0..width).min_by_key(|&s| variance(positions(s).map(|(x, _)| x)))?;
+                let y_seconds =
+                    (0..height).min_by_key(|&s| variance(positions(s).map(|(_, y)| y)))?;
+                (0..height)
+                    .map(|k| x_seconds + k * width)
+                    .find(|seconds| seconds % height == y_seconds)
+            }
+        }
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut robots = input
-        .lines()
-        .map(|line| parse_line(line))
-        .collect::<Vec<_>>();
+/// The variance of `values`, scaled by the square of their number to stay an integer.
+fn variance(values: impl Iterator<Item = usize>) -> u64 {
+    let (n, sum, sum_of_squares) = values.fold((0, 0, 0), |(n, sum, squares), v| {
+        let v = v as u64;
+        (n + 1, sum + v, squares + v * v)
+    });
+    n * sum_of_squares - sum * sum
+}
 
-    let original_robots = robots.clone();
+/// Seconds until the robots show the Christmas tree, found with the `detector` named like in [`Detector`].
+///
+/// With `--export <dir>`, also writes the first 10,000 seconds as contact sheets of 10 x 10 seconds.
+fn tree_seconds(input: &str, detector: &str) -> Result<Option<u32>, Box<dyn Error>> {
+    let robots = parse_input(input)?;
+    let detector: Detector = detector.parse()?;
+
+    let seconds = detector.find(&robots, WIDTH, HEIGHT);
+
+    let mut animation = Animation::new();
+    if let Some(seconds) = seconds {
+        animation.record(|| {
+            let robots = robots_after(&robots, seconds, WIDTH, HEIGHT);
+            robots_frame(&robots, WIDTH - 1, HEIGHT - 1)
+                .with_caption(format!("after {seconds} seconds"))
+        });
+    }
+    animation.play();
 
-    // problem constraints
-    let max_x = 101 - 1;
-    let max_y = 103 - 1;
+    if let Some(dir) = export::dir() {
+        let mut sheets = ContactSheets::new(dir, 10, 10)?;
+        for seconds in 1..=10_000 {
+            let robots = robots_after(&robots, seconds, WIDTH, HEIGHT);
+            sheets.push(robots_image(&robots, WIDTH - 1, HEIGHT - 1))?;
+        }
+        sheets.finish()?;
+    }
 
-    for i in 0..10_000 {
-        robots
-            .iter_mut()
-            .for_each(|robot| robot.step(1, max_x, max_y));
+    Ok(seconds)
+}
 
-        pretty_print_robots_on_grid(&robots, max_x, max_y, i, "output.txt");
-    }
+/// Uses the detector passed with `--detector <name>`, `crt` by default.
+pub fn part_two(input: &str) -> Result<Option<u32>, Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    let detector = args
+        .iter()
+        .position(|x| x == "--detector")
+        .and_then(|i| args.get(i + 1))
+        .map_or("crt", String::as_str);
 
-    None
+    tree_seconds(input, detector)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::property::Rng;
+
+    advent_of_code::example_tests!();
+
+    /// Seconds until the robots of [`tree_robots`] show their picture.
+    const TREE_SECONDS: u32 = 1234;
+
+    /// 500 made up robots with random velocities. After [`TREE_SECONDS`] they draw a framed
+    /// triangle, with the rest scattered around it and no two robots on the same tile.
+    fn tree_robots() -> String {
+        let (left, top, right, bottom) = (40, 30, 70, 62);
+        let mut tiles = vec![];
+        for x in left..=right {
+            tiles.extend([(x, top), (x, bottom)]);
+        }
+        for y in top + 1..bottom {
+            tiles.extend([(left, y), (right, y)]);
+        }
+        for row in 0..14 {
+            tiles.extend((55 - row..=55 + row).map(|x| (x, top + 5 + row)));
+        }
+
+        let mut rng = Rng::new(14);
+        while tiles.len() < 500 {
+            let tile = (
+                rng.between(0, u64::from(WIDTH) - 1) as i64,
+                rng.between(0, u64::from(HEIGHT) - 1) as i64,
+            );
+            let in_frame = (left..=right).contains(&tile.0) && (top..=bottom).contains(&tile.1);
+            if !in_frame && !tiles.contains(&tile) {
+                tiles.push(tile);
+            }
+        }
+
+        let seconds = i64::from(TREE_SECONDS);
+        tiles
+            .into_iter()
+            .map(|(x, y)| {
+                let vx = rng.between(0, 198) as i64 - 99;
+                let vy = rng.between(0, 198) as i64 - 99;
+                let x = (x - vx * seconds).rem_euclid(i64::from(WIDTH));
+                let y = (y - vy * seconds).rem_euclid(i64::from(HEIGHT));
+                format!("p={x},{y} v={vx},{vy}\n")
+            })
+            .collect()
+    }
 
     #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(12));
+    fn detectors_find_the_tree() {
+        let input = tree_robots();
+        for detector in ["variance", "no_overlap", "longest_run", "crt"] {
+            let seconds = tree_seconds(&input, detector).unwrap();
+            assert_eq!(seconds, Some(TREE_SECONDS), "{detector}");
+        }
     }
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+    fn unknown_detector_is_an_error() {
+        let error = tree_seconds(&tree_robots(), "fourier").unwrap_err();
+        assert!(error.to_string().starts_with("unknown detector"));
     }
 }
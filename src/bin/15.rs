@@ -1,139 +1,93 @@
 advent_of_code::solution!(15);
 
-fn parse_input(input: &str) -> (Vec<Vec<char>>, Vec<char>) {
+use advent_of_code::grid::{Direction, Grid, Point};
+use advent_of_code::visualize::{Animation, Frame};
+use colored::Color;
+
+fn parse_input(input: &str) -> (Grid<char>, Vec<Direction>) {
     let mut parts = input.split("\n\n");
-    let grid = parts
+    let grid = Grid::parse(parts.next().unwrap());
+
+    let path = parts
         .next()
         .unwrap()
-        .lines()
-        .map(|line| line.chars().collect())
+        .chars()
+        .filter_map(Direction::from_arrow)
         .collect();
 
-    let path = parts.next().unwrap().trim().chars().collect();
-
     (grid, path)
 }
 
-fn print_grid(grid: &Vec<Vec<char>>) {
-    for row in grid {
-        println!("{}", row.iter().collect::<String>());
+fn take_step(grid: &mut Grid<char>, robot: Point, step: Direction) -> Point {
+    let next = robot.step(step);
+
+    // find the first cell behind the boxes in front of the robot
+    let mut k = next;
+    while grid[k] == 'O' {
+        k = k.step(step);
     }
-}
 
-fn take_step(grid: &mut Vec<Vec<char>>, robot: (usize, usize), step: char) -> (usize, usize) {
-    let (mut rx, mut ry) = robot;
-    match step {
-        '>' => {
-            let mut k = ry + 1;
-            while grid[rx][k] == 'O' {
-                k += 1;
-            }
-            if grid[rx][k] == '.' {
-                // push the block to the right
-                grid[rx][k] = grid[rx][ry + 1];
-                grid[rx][ry + 1] = '.';
-                // move the robot
-                grid[rx][ry + 1] = '@';
-                grid[rx][ry] = '.';
-                ry = ry + 1;
-            }
-        }
-        '<' => {
-            let mut k = ry - 1;
-            while grid[rx][k] == 'O' {
-                k -= 1;
-            }
-            if grid[rx][k] == '.' {
-                // swap
-                grid[rx][k] = grid[rx][ry - 1];
-                grid[rx][ry - 1] = '.';
-                // // move the robot
-                grid[rx][ry - 1] = '@';
-                grid[rx][ry] = '.';
-                ry = ry - 1;
-            }
-        }
-        'v' => {
-            let mut k = rx + 1;
-            while grid[k][ry] == 'O' {
-                k += 1;
-            }
-            if grid[k][ry] == '.' {
-                // swap
-                grid[k][ry] = grid[rx + 1][ry];
-                grid[rx + 1][ry] = '.';
-                // move the robot
-                grid[rx + 1][ry] = '@';
-                grid[rx][ry] = '.';
-
-                rx = rx + 1;
-            }
-        }
-        '^' => {
-            let mut k = rx - 1;
-            while grid[k][ry] == 'O' {
-                k -= 1;
-            }
-            if grid[k][ry] == '.' {
-                // swap
-                grid[k][ry] = grid[rx - 1][ry];
-                grid[rx - 1][ry] = '.';
-                // move the robot
-                grid[rx - 1][ry] = '@';
-                grid[rx][ry] = '.';
-
-                rx = rx - 1;
-            }
-        }
-        _ => {}
+    if grid[k] != '.' {
+        return robot;
     }
 
-    (rx, ry)
+    // push the boxes by moving the first one to the free cell
+    grid[k] = grid[next];
+    grid[next] = '.';
+    // move the robot
+    grid[next] = '@';
+    grid[robot] = '.';
+
+    next
 }
 
-fn simulate_path(grid: &Vec<Vec<char>>, path: &Vec<char>) -> Vec<Vec<char>> {
+fn simulate_path(grid: &Grid<char>, path: &[Direction]) -> Grid<char> {
     let mut grid = grid.clone();
 
-    // find the start (i,j) of the @ sign
-    let mut rx = 0;
-    let mut ry = 0;
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == '@' {
-                rx = i;
-                ry = j;
-                grid[i][j] = '.';
-                break;
-            }
-        }
+    // find the start of the @ sign
+    let mut robot = grid.find(&'@').unwrap();
+    grid[robot] = '.';
+
+    let mut animation = Animation::new();
+    animation.record(|| warehouse_frame(&grid, robot).with_caption("start"));
+
+    for (i, direction) in path.iter().enumerate() {
+        robot = take_step(&mut grid, robot, *direction);
+        animation.record(|| {
+            warehouse_frame(&grid, robot).with_caption(format!(
+                "move {}: {}",
+                i + 1,
+                direction.arrow()
+            ))
+        });
     }
 
-    for direction in path {
-        (rx, ry) = take_step(&mut grid, (rx, ry), *direction);
-        // println!("{}", direction);
-        // print_grid(&grid);
-        // println!("---------------------------");
-    }
+    animation.play();
 
     grid
 }
 
+fn warehouse_frame(grid: &Grid<char>, robot: Point) -> Frame {
+    Frame::from_grid(grid, |_, c| *c)
+        .highlight(
+            grid.positions(|c| matches!(c, 'O' | '[' | ']')),
+            Color::Cyan,
+        )
+        .mark(robot, '@', Color::Yellow)
+}
+
+fn gps_sum(grid: &Grid<char>, cell: char) -> u64 {
+    grid.find_all(&cell)
+        .map(|p| p.y as u64 * 100 + p.x as u64)
+        .sum()
+}
+
 pub fn part_one(input: &str) -> Option<u64> {
     let (grid, path) = parse_input(input);
 
     let grid = simulate_path(&grid, &path);
 
-    let mut sum = 0;
-
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'O' {
-                sum += i as u64 * 100 + j as u64;
-            }
-        }
-    }
-
-    Some(sum)
+    Some(gps_sum(&grid, 'O'))
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -155,33 +109,13 @@ impl std::fmt::Display for BlockType {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Block {
-    x_left: u32,
-    x_right: u32,
-    y: u32,
-    block_type: BlockType,
-}
-
-impl Block {
-    fn new(x: u32, y: u32, block_type: BlockType) -> Self {
-        Block {
-            x_left: x,
-            x_right: x + 1,
-            y,
-            block_type,
-        }
-    }
-}
-
-fn convert_block_grid(grid: &Vec<Vec<Block>>) -> Vec<Vec<char>> {
-    grid.iter()
-        .map(|row| {
-            row.iter()
-                .flat_map(|block| block.block_type.to_string().chars().collect::<Vec<_>>())
-                .collect()
-        })
-        .collect()
+fn convert_block_grid(grid: &Grid<BlockType>) -> Grid<char> {
+    let cells = grid
+        .cells()
+        .iter()
+        .flat_map(|block| block.to_string().chars().collect::<Vec<_>>())
+        .collect();
+    Grid::from_vec(grid.width() * 2, grid.height(), cells)
 }
 
 // fn next_position(direction: char, current_block: Block, grid: &Vec<Vec<char>>) -> (u32, u32, u32) {
@@ -232,64 +166,31 @@ fn convert_block_grid(grid: &Vec<Vec<Block>>) -> Vec<Vec<char>> {
 //     }
 // }
 
-fn simulate_path_blocks(grid: &Vec<Vec<char>>, path: &Vec<char>) -> Vec<Vec<char>> {
-    let mut grid = grid.clone();
-
-    grid
-}
-
 pub fn part_two(input: &str) -> Option<u64> {
-    let (grid, path) = parse_input(input);
+    let (grid, _path) = parse_input(input);
 
     // convert the grid to a block grid
-    let mut block_grid = Vec::new();
-    for i in 0..grid.len() {
-        let mut row = Vec::new();
-        for j in 0..grid[i].len() {
-            match grid[i][j] {
-                '.' => row.push(Block::new(2 * i as u32, j as u32, BlockType::Empty)),
-                '#' => row.push(Block::new(2 * i as u32, j as u32, BlockType::Wall)),
-                'O' => row.push(Block::new(2 * i as u32, j as u32, BlockType::Box)),
-                '@' => row.push(Block::new(2 * i as u32, j as u32, BlockType::Robot)),
-                '\n' => continue,
-                _ => panic!("Invalid character in grid"),
-            }
-        }
-        block_grid.push(row);
-    }
+    let block_grid = grid.map(|c| match c {
+        '.' => BlockType::Empty,
+        '#' => BlockType::Wall,
+        'O' => BlockType::Box,
+        '@' => BlockType::Robot,
+        _ => panic!("Invalid character in grid"),
+    });
 
     let grid = convert_block_grid(&block_grid);
 
-    print_grid(&grid);
+    let mut animation = Animation::new();
+    animation.record(|| Frame::from_grid(&grid, |_, c| *c).with_caption("wide warehouse"));
+    animation.play();
 
-    return None;
-
-    let mut sum = 0;
-
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'O' {
-                sum += i as u64 * 100 + j as u64;
-            }
-        }
-    }
-
-    Some(sum)
+    // TODO: simulate the path on the wide grid and sum the boxes with `gps_sum(&grid, '[')`
+    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(10092));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
-    }
+    advent_of_code::example_tests!();
 }
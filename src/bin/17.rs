@@ -1,323 +1,503 @@
-advent_of_code::solution!(17);
-use regex::Regex;
-
-#[derive(Debug, Clone, Copy)]
-struct Register {
-    val: usize,
-    name: char,
-}
-
-impl Register {
-    fn new(name: char, val: usize) -> Self {
-        Self { val, name }
+advent_of_code::solution!(17, parse = Machine::parse);
+
+use advent_of_code::log;
+use std::env;
+
+use vm::{Machine, Registers};
+
+/// The 3-bit computer: execution with breakpoints and traces, a disassembler and a debugger.
+pub mod vm {
+    use std::collections::BTreeSet;
+    use std::fmt::{self, Display};
+    use std::io::{self, BufRead, Write};
+
+    use advent_of_code::parse::{ParseError, Parser};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Opcode {
+        Adv,
+        Bxl,
+        Bst,
+        Jnz,
+        Bxc,
+        Out,
+        Bdv,
+        Cdv,
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Code {
-    adv,
-    bxl,
-    bst,
-    jnz,
-    bxc,
-    out,
-    bdv,
-    cdv,
-}
 
-impl From<u8> for Code {
-    fn from(code: u8) -> Self {
-        match code {
-            0 => Code::adv,
-            1 => Code::bxl,
-            2 => Code::bst,
-            3 => Code::jnz,
-            4 => Code::bxc,
-            5 => Code::out,
-            6 => Code::bdv,
-            7 => Code::cdv,
-            _ => unreachable!(),
+    impl From<u8> for Opcode {
+        fn from(code: u8) -> Self {
+            match code & 7 {
+                0 => Opcode::Adv,
+                1 => Opcode::Bxl,
+                2 => Opcode::Bst,
+                3 => Opcode::Jnz,
+                4 => Opcode::Bxc,
+                5 => Opcode::Out,
+                6 => Opcode::Bdv,
+                _ => Opcode::Cdv,
+            }
         }
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-enum OperandType {
-    Combo,
-    Literal,
-    Nil,
-}
-
-impl From<Code> for OperandType {
-    fn from(code: Code) -> Self {
-        match code {
-            Code::adv => OperandType::Combo,
-            Code::bxl => OperandType::Literal,
-            Code::bst => OperandType::Combo,
-            Code::jnz => OperandType::Literal,
-            Code::bxc => OperandType::Nil,
-            Code::out => OperandType::Combo,
-            Code::bdv => OperandType::Combo,
-            Code::cdv => OperandType::Combo,
-        }
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum OperandKind {
+        Combo,
+        Literal,
+        Ignored,
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-struct CodeInstruction {
-    code: Code,
-}
+    impl Opcode {
+        pub fn name(self) -> &'static str {
+            match self {
+                Opcode::Adv => "adv",
+                Opcode::Bxl => "bxl",
+                Opcode::Bst => "bst",
+                Opcode::Jnz => "jnz",
+                Opcode::Bxc => "bxc",
+                Opcode::Out => "out",
+                Opcode::Bdv => "bdv",
+                Opcode::Cdv => "cdv",
+            }
+        }
 
-#[derive(Debug, Clone, Copy)]
-struct OperandInstruction {
-    operand: u8,
-    operand_type: OperandType,
-}
+        fn operand_kind(self) -> OperandKind {
+            match self {
+                Opcode::Bxl | Opcode::Jnz => OperandKind::Literal,
+                Opcode::Bxc => OperandKind::Ignored,
+                _ => OperandKind::Combo,
+            }
+        }
+    }
 
-#[derive(Debug, Clone, Copy)]
-enum Instruction {
-    Code(CodeInstruction),
-    Operand(OperandInstruction),
-}
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Instruction {
+        pub opcode: Opcode,
+        pub operand: u8,
+    }
 
-struct Machine {
-    a: Register,
-    b: Register,
-    c: Register,
-    program: Vec<Instruction>,
-    halt: bool,
-    counter: usize,
-}
+    impl Instruction {
+        /// The operand as the listing shows it: combo operands 4 to 6 name the registers.
+        fn operand_name(self) -> String {
+            match (self.opcode.operand_kind(), self.operand) {
+                (OperandKind::Combo, 4) => "a".to_string(),
+                (OperandKind::Combo, 5) => "b".to_string(),
+                (OperandKind::Combo, 6) => "c".to_string(),
+                (OperandKind::Combo, 7) => "?".to_string(),
+                (_, operand) => operand.to_string(),
+            }
+        }
 
-impl Machine {
-    fn parse_and_init(input: &str) -> Self {
-        // Parse register initializations
-        let reg_pattern = Regex::new(r"Register ([A-C]): (\d+)").unwrap();
-        let mut a = Register::new('A', 0);
-        let mut b = Register::new('B', 0);
-        let mut c = Register::new('C', 0);
-
-        // Parse program instructions
-        let prog_pattern = Regex::new(r"Program: ([\d,]+)").unwrap();
-        let mut program = Vec::new();
-
-        for line in input.lines() {
-            if let Some(caps) = reg_pattern.captures(line) {
-                let reg_name = caps[1].chars().next().unwrap();
-                let value = caps[2].parse::<i32>().unwrap();
-                match reg_name {
-                    'A' => a = Register::new('A', value as usize),
-                    'B' => b = Register::new('B', value as usize),
-                    'C' => c = Register::new('C', value as usize),
-                    _ => unreachable!(),
-                }
-            } else if let Some(caps) = prog_pattern.captures(line) {
-                let numbers: Vec<u8> = caps[1]
-                    .split(',')
-                    .map(|n| n.parse::<u8>().unwrap())
-                    .collect();
-
-                // Process numbers alternating between code and operand
-                program = numbers
-                    .chunks(2)
-                    .flat_map(|chunk| {
-                        let code = Code::from(chunk[0]);
-                        [
-                            Instruction::Code(CodeInstruction { code }),
-                            Instruction::Operand(OperandInstruction {
-                                operand: chunk[1],
-                                operand_type: OperandType::from(code),
-                            }),
-                        ]
-                    })
-                    .collect();
+        /// What the instruction does, written as pseudo code.
+        pub fn effect(self) -> String {
+            let operand = self.operand_name();
+            match self.opcode {
+                Opcode::Adv => format!("a = a >> {operand}"),
+                Opcode::Bxl => format!("b = b ^ {operand}"),
+                Opcode::Bst => format!("b = {operand} % 8"),
+                Opcode::Jnz => format!("if a != 0 goto {operand}"),
+                Opcode::Bxc => "b = b ^ c".to_string(),
+                Opcode::Out => format!("out {operand} % 8"),
+                Opcode::Bdv => format!("b = a >> {operand}"),
+                Opcode::Cdv => format!("c = a >> {operand}"),
             }
         }
+    }
 
-        Self {
-            a,
-            b,
-            c,
-            program,
-            halt: false,
-            counter: 0,
+    impl Display for Instruction {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.opcode.operand_kind() {
+                OperandKind::Ignored => write!(f, "{}", self.opcode.name()),
+                _ => write!(f, "{} {}", self.opcode.name(), self.operand_name()),
+            }
         }
     }
 
-    fn get_combo_value(&self, operand: u8) -> usize {
-        match operand {
-            0..4 => operand as usize,
-            4 => self.a.val,
-            5 => self.b.val,
-            6 => self.c.val,
-            _ => unreachable!(),
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct Registers {
+        pub a: u64,
+        pub b: u64,
+        pub c: u64,
+    }
+
+    impl Display for Registers {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a={} b={} c={}", self.a, self.b, self.c)
         }
     }
 
-    fn one_step(&mut self, opcode: CodeInstruction, operand: OperandInstruction) -> Option<u8> {
-        if self.halt {
-            return None;
+    /// One executed instruction, with the registers after it ran. A trace is a sequence of steps.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Step {
+        pub pc: usize,
+        pub instruction: Instruction,
+        pub registers: Registers,
+        pub output: Option<u8>,
+    }
+
+    impl Display for Step {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let instruction = self.instruction.to_string();
+            write!(f, "{:>3}: {instruction:<8}{}", self.pc, self.registers)?;
+            if let Some(output) = self.output {
+                write!(f, " out={output}")?;
+            }
+            Ok(())
         }
+    }
 
-        let op_val = match operand.operand_type {
-            OperandType::Combo => self.get_combo_value(operand.operand),
-            OperandType::Literal => operand.operand as usize,
-            OperandType::Nil => 0,
-        };
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Stop {
+        Halted,
+        Breakpoint(usize),
+    }
 
-        // println!("Instruction\t: Opcode {:?}\tOperand {:?}", opcode, operand);
-        // println!("Op Val: {}", op_val);
+    #[derive(Debug, Clone)]
+    pub struct Machine {
+        pub registers: Registers,
+        initial: Registers,
+        program: Vec<u8>,
+        pc: usize,
+        output: Vec<u8>,
+        breakpoints: BTreeSet<usize>,
+    }
 
-        let mut jump = false;
-        let mut print_output = None;
+    impl Machine {
+        pub fn parse(input: &str) -> Result<Self, ParseError> {
+            let mut parser = Parser::new(input);
 
-        match opcode.code {
-            Code::adv => {
-                self.a.val = self.a.val as usize / 2usize.pow(op_val as u32);
-            }
-            Code::bxl => {
-                self.b.val = self.b.val ^ op_val;
-            }
-            Code::bst => {
-                self.b.val = op_val % 8;
-            }
-            Code::jnz => {
-                if self.a.val != 0 {
-                    self.counter = op_val as usize;
-                    jump = true;
+            let registers =
+                parser.pattern::<u64>("Register A: {}\nRegister B: {}\nRegister C: {}")?;
+            parser.take_while(char::is_whitespace);
+
+            parser.literal("Program: ")?;
+            let program = parser.separated(",", |p| {
+                let n: u8 = p.integer()?;
+                if n > 7 {
+                    return Err(p.error(format!("{n} is not a 3-bit number")));
                 }
+                Ok(n)
+            })?;
+            if program.len() % 2 != 0 {
+                return Err(parser.error("expected an operand after the last opcode"));
             }
-            Code::bxc => {
-                self.b.val = self.b.val ^ self.c.val;
-            }
-            Code::out => {
-                // print!("{},", op_val % 8);
-                print_output = Some((op_val % 8) as u8);
-            }
-            Code::bdv => {
-                self.b.val = self.a.val as usize / 2usize.pow(op_val as u32);
-            }
-            Code::cdv => {
-                self.c.val = self.a.val as usize / 2usize.pow(op_val as u32);
+            let reserved = program.chunks(2).any(|pair| {
+                Opcode::from(pair[0]).operand_kind() == OperandKind::Combo && pair[1] == 7
+            });
+            if reserved {
+                return Err(parser.error("combo operand 7 is reserved"));
             }
+            parser.end()?;
+
+            let registers = Registers {
+                a: registers[0],
+                b: registers[1],
+                c: registers[2],
+            };
+            Ok(Self {
+                registers,
+                initial: registers,
+                program,
+                pc: 0,
+                output: Vec::new(),
+                breakpoints: BTreeSet::new(),
+            })
         }
 
-        if !jump {
-            self.counter += 2;
+        pub fn program(&self) -> &[u8] {
+            &self.program
         }
 
-        if self.counter >= self.program.len() {
-            self.halt = true;
+        pub fn pc(&self) -> usize {
+            self.pc
         }
 
-        print_output
-    }
+        pub fn output(&self) -> &[u8] {
+            &self.output
+        }
 
-    fn print_state(&self) {
-        println!("Register A: {}", self.a.val);
-        println!("Register B: {}", self.b.val);
-        println!("Register C: {}", self.c.val);
-        println!("Program Counter: {}", self.counter);
-        println!("Halt: {}", self.halt);
-    }
+        /// The output so far, joined with commas.
+        pub fn output_string(&self) -> String {
+            self.output
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
 
-    fn run(&mut self) {
-        // self.print_state();
-        // println!("{:?}", self.program);
-        let mut result: Vec<u8> = Vec::new();
+        /// The machine halts when the instruction pointer leaves the program.
+        pub fn is_halted(&self) -> bool {
+            self.instruction_at(self.pc).is_none()
+        }
 
-        while !self.halt {
-            // println!("--------------------------------");
+        pub fn instruction_at(&self, pc: usize) -> Option<Instruction> {
+            let opcode = *self.program.get(pc)?;
+            let operand = *self.program.get(pc + 1)?;
+            Some(Instruction {
+                opcode: Opcode::from(opcode),
+                operand,
+            })
+        }
 
-            let Instruction::Code(code) = self.program[self.counter] else {
-                unreachable!()
-            };
-            let Instruction::Operand(operand) = self.program[self.counter + 1] else {
-                unreachable!()
-            };
+        /// Starts over with `registers`, keeping the breakpoints.
+        pub fn reset(&mut self, registers: Registers) {
+            self.registers = registers;
+            self.pc = 0;
+            self.output.clear();
+        }
 
-            if let Some(print_output) = self.one_step(code, operand) {
-                result.push(print_output);
+        /// Starts over with the registers from the input.
+        pub fn restart(&mut self) {
+            self.reset(self.initial);
+        }
+
+        /// Sets or clears the breakpoint at `pc`, returns whether it is set now.
+        pub fn toggle_breakpoint(&mut self, pc: usize) -> bool {
+            if self.breakpoints.remove(&pc) {
+                false
+            } else {
+                self.breakpoints.insert(pc);
+                true
             }
         }
 
-        println!("--------------------------------");
-        println!("{:?}", result);
-    }
+        pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+            self.breakpoints.iter().copied()
+        }
 
-    fn run_and_get_output(&mut self) -> Vec<u8> {
-        let mut result: Vec<u8> = Vec::new();
+        fn combo(&self, operand: u8) -> u64 {
+            match operand {
+                0..4 => operand as u64,
+                4 => self.registers.a,
+                5 => self.registers.b,
+                6 => self.registers.c,
+                _ => panic!("combo operand 7 is reserved"),
+            }
+        }
 
-        while !self.halt {
-            let Instruction::Code(code) = self.program[self.counter] else {
-                unreachable!()
-            };
-            let Instruction::Operand(operand) = self.program[self.counter + 1] else {
-                unreachable!()
+        /// Executes one instruction, `None` if the machine has halted.
+        pub fn step(&mut self) -> Option<Step> {
+            let pc = self.pc;
+            let instruction = self.instruction_at(pc)?;
+            let operand = instruction.operand;
+            // Dividing by 2^n is a shift, which leaves 0 once n reaches the register width.
+            let shift = match instruction.opcode.operand_kind() {
+                OperandKind::Combo => u32::try_from(self.combo(operand)).unwrap_or(u32::MAX),
+                _ => 0,
             };
+            let shifted = |value: u64| value.checked_shr(shift).unwrap_or(0);
+
+            let mut output = None;
+            self.pc += 2;
+            match instruction.opcode {
+                Opcode::Adv => self.registers.a = shifted(self.registers.a),
+                Opcode::Bxl => self.registers.b ^= operand as u64,
+                Opcode::Bst => self.registers.b = self.combo(operand) % 8,
+                Opcode::Jnz => {
+                    if self.registers.a != 0 {
+                        self.pc = operand as usize;
+                    }
+                }
+                Opcode::Bxc => self.registers.b ^= self.registers.c,
+                Opcode::Out => output = Some((self.combo(operand) % 8) as u8),
+                Opcode::Bdv => self.registers.b = shifted(self.registers.a),
+                Opcode::Cdv => self.registers.c = shifted(self.registers.a),
+            }
+            self.output.extend(output);
 
-            if let Some(print_output) = self.one_step(code, operand) {
-                result.push(print_output);
+            Some(Step {
+                pc,
+                instruction,
+                registers: self.registers,
+                output,
+            })
+        }
+
+        /// Runs until the machine halts or reaches a breakpoint, calling `on_step` after every instruction.
+        /// Always executes at least one instruction, so running again moves past the breakpoint it stopped at.
+        pub fn run(&mut self, mut on_step: impl FnMut(&Step)) -> Stop {
+            while let Some(step) = self.step() {
+                on_step(&step);
+                if self.breakpoints.contains(&self.pc) && !self.is_halted() {
+                    return Stop::Breakpoint(self.pc);
+                }
             }
+            Stop::Halted
         }
 
-        result
-    }
+        /// Runs until the machine halts, ignoring breakpoints.
+        pub fn run_to_end(&mut self, mut on_step: impl FnMut(&Step)) -> &[u8] {
+            while let Some(step) = self.step() {
+                on_step(&step);
+            }
+            &self.output
+        }
 
-    fn reset(&mut self, a: usize, b: usize, c: usize) {
-        self.a = Register::new('A', a);
-        self.b = Register::new('B', b);
-        self.c = Register::new('C', c);
-        self.counter = 0;
-        self.halt = false;
+        /// A listing of the program, one instruction per line, marking the instruction pointer with `>`
+        /// and breakpoints with `*`.
+        pub fn disassemble(&self) -> String {
+            (0..self.program.len())
+                .step_by(2)
+                .filter_map(|pc| self.instruction_at(pc).map(|i| (pc, i)))
+                .map(|(pc, instruction)| {
+                    let marker = if pc == self.pc { '>' } else { ' ' };
+                    let breakpoint = if self.breakpoints.contains(&pc) {
+                        '*'
+                    } else {
+                        ' '
+                    };
+                    let listed = instruction.to_string();
+                    format!(
+                        "{marker}{breakpoint}{pc:>3}: {listed:<8}; {}\n",
+                        instruction.effect()
+                    )
+                })
+                .collect()
+        }
     }
 
-    fn program_to_vec(&self) -> Vec<u8> {
-        self.program
-            .iter()
-            .map(|i| match i {
-                Instruction::Code(code) => code.code as u8,
-                Instruction::Operand(operand) => operand.operand,
-            })
-            .collect()
+    const DEBUGGER_HELP: &str = "\
+s [count]      step one or `count` instructions
+c              continue to the next breakpoint or the end
+b <pc>         set or clear a breakpoint
+r              show the registers, instruction pointer and output
+set <a|b|c> <value>  change a register
+l              list the program
+t              toggle printing every instruction on continue
+reset [a]      start over, optionally with another value in register a
+h              show this help
+q              quit";
+
+    /// A line based debugger reading commands from stdin and answering on stderr, so stdout keeps
+    /// only the answers. Returns when the user quits or stdin closes.
+    pub fn debug(machine: &mut Machine) {
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        let mut trace = false;
+
+        eprintln!("{}", machine.disassemble());
+        eprintln!("{DEBUGGER_HELP}");
+        loop {
+            eprint!("({}) ", machine.pc());
+            // A failed flush only delays the prompt.
+            let _ = io::stderr().flush();
+            let Some(Ok(line)) = lines.next() else {
+                break;
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["s"] | ["s", _] => {
+                    let count = match words.get(1).map(|x| x.parse::<usize>()) {
+                        None => 1,
+                        Some(Ok(count)) => count,
+                        Some(Err(_)) => {
+                            eprintln!("expected a number of steps");
+                            continue;
+                        }
+                    };
+                    for _ in 0..count {
+                        match machine.step() {
+                            Some(step) => eprintln!("{step}"),
+                            None => {
+                                eprintln!("halted");
+                                break;
+                            }
+                        }
+                    }
+                }
+                ["c"] => {
+                    let stop = machine.run(|step| {
+                        if trace {
+                            eprintln!("{step}");
+                        }
+                    });
+                    match stop {
+                        Stop::Halted => eprintln!("halted, output {}", machine.output_string()),
+                        Stop::Breakpoint(pc) => eprintln!("breakpoint at {pc}"),
+                    }
+                }
+                ["b", pc] => match pc.parse() {
+                    Ok(pc) if machine.instruction_at(pc).is_some() => {
+                        let set = machine.toggle_breakpoint(pc);
+                        eprintln!("breakpoint at {pc} {}", if set { "set" } else { "cleared" });
+                    }
+                    _ => eprintln!("{pc} is not an instruction of the program"),
+                },
+                ["r"] => {
+                    eprintln!("{} pc={}", machine.registers, machine.pc());
+                    eprintln!("output {}", machine.output_string());
+                }
+                ["set", register, value] => {
+                    let Ok(value) = value.parse() else {
+                        eprintln!("expected a number, got {value:?}");
+                        continue;
+                    };
+                    match *register {
+                        "a" => machine.registers.a = value,
+                        "b" => machine.registers.b = value,
+                        "c" => machine.registers.c = value,
+                        _ => eprintln!("unknown register {register:?}"),
+                    }
+                }
+                ["l"] => eprint!("{}", machine.disassemble()),
+                ["t"] => {
+                    trace = !trace;
+                    eprintln!("trace {}", if trace { "on" } else { "off" });
+                }
+                ["reset"] => machine.restart(),
+                ["reset", a] => match a.parse() {
+                    Ok(a) => machine.reset(Registers {
+                        a,
+                        ..machine.initial
+                    }),
+                    Err(_) => eprintln!("expected a number, got {a:?}"),
+                },
+                ["h"] => eprintln!("{DEBUGGER_HELP}"),
+                ["q"] => break,
+                _ => eprintln!("unknown command {line:?}, `h` lists the commands"),
+            }
+        }
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut machine = Machine::parse_and_init(input);
+/// Runs the program and joins its output with commas.
+///
+/// With `--verbose`, logs the listing and every executed instruction. With `--debug` (`cargo solve 17 -- --debug`), steps through
+/// the program in [`vm::debug`] before it runs to the end.
+pub fn part_one(machine: &Machine) -> String {
+    let mut machine = machine.clone();
 
-    machine.run();
+    log::debug!("program:\n{}", machine.disassemble().trim_end());
+    if env::args().any(|x| x == "--debug") {
+        vm::debug(&mut machine);
+    }
+    machine.run_to_end(|step| log::debug!("{step}"));
 
-    None
+    machine.output_string()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut machine = Machine::parse_and_init(input);
+pub fn part_two(machine: &Machine) -> Option<u64> {
+    let mut machine = machine.clone();
 
-    let target_sequence = machine.program_to_vec();
+    let target_sequence = machine.program().to_vec();
     let n = target_sequence.len();
 
-    let mut a: usize = 0;
+    let mut a: u64 = 0;
     for i in (0..n).rev() {
         a <<= 3;
 
         loop {
-            machine.reset(a, 0, 0);
-            let output = machine.run_and_get_output();
+            machine.reset(Registers { a, b: 0, c: 0 });
+            let output = machine.run_to_end(|_| {});
 
-            if output == target_sequence[i..] {
+            if output == &target_sequence[i..] {
                 break;
             }
             a += 1;
         }
     }
 
-    println!("--------------part two---------------");
-    println!("{:?}", target_sequence);
-    println!("--------------------------------");
-    println!("{}", a);
-    println!("--------------------------------");
-
     Some(a)
 }
 
@@ -325,15 +505,15 @@ pub fn part_two(input: &str) -> Option<usize> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
-    }
+    advent_of_code::example_tests!();
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(117440));
+    fn disassembly() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let machine = Machine::parse(&input).unwrap();
+        assert_eq!(
+            machine.disassemble(),
+            ">   0: adv 1   ; a = a >> 1\n    2: out a   ; out a % 8\n    4: jnz 0   ; if a != 0 goto 0\n"
+        );
     }
 }
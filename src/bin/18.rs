@@ -1,132 +1,136 @@
 advent_of_code::solution!(18);
 
-use regex::Regex;
-use std::collections::VecDeque;
+use advent_of_code::graph::UnionFind;
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::parse::{ParseError, Parser};
+use advent_of_code::pathfinding;
+use advent_of_code::visualize::{Animation, Frame};
+use colored::Color;
 
 const MAX_BYTES: usize = 1024;
 const MAX_SIZE: usize = 71;
 
-// const MAX_BYTES: usize = 12;
-// const MAX_SIZE: usize = 7;
-
-fn parse_input(input: &str) -> Vec<(usize, usize)> {
-    let re = Regex::new(r"(?P<x>\d+),(?P<y>\d+)").unwrap();
-    input
-        .lines()
-        .map(|line| {
-            let captures = re.captures(line).unwrap();
-            (
-                captures["x"].parse().unwrap(),
-                captures["y"].parse().unwrap(),
-            )
-        })
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<Point>, ParseError> {
+    Parser::new(input).lines(|p| {
+        let v = p.pattern::<u16>("{},{}")?;
+        Ok(Point::new(v[0].into(), v[1].into()))
+    })
 }
 
-fn create_grid(points: &Vec<(usize, usize)>, max_bytes: usize) -> Vec<Vec<u32>> {
-    let mut grid = vec![vec![0; MAX_SIZE]; MAX_SIZE];
-    for &(x, y) in points.iter().take(max_bytes) {
-        grid[y][x] = 1;
+/// A `size` x `size` grid where the first `max_bytes` points are corrupted.
+fn create_grid(points: &[Point], size: usize, max_bytes: usize) -> Grid<bool> {
+    let mut grid = Grid::new(size, size, false);
+    for &p in points.iter().take(max_bytes) {
+        grid[p] = true;
     }
     grid
 }
 
-fn smallest_path(grid: &Vec<Vec<u32>>, start: (usize, usize), end: (usize, usize)) -> Option<u32> {
-    let mut visited = vec![vec![false; grid[0].len()]; grid.len()];
-    // let mut path = Vec::new();
-    // path.push(start);
+/// The shortest path from `start` to `end`, including both.
+fn shortest_path(grid: &Grid<bool>, start: Point, end: Point) -> Option<Vec<Point>> {
+    let (_, path) = pathfinding::bfs(
+        start,
+        |p| grid.neighbors4(*p).filter(|n| !grid[*n]),
+        |p| *p == end,
+    )?;
 
-    let mut queue = VecDeque::new();
-    queue.push_back((start, 0));
-    visited[start.0][start.1] = true;
-
-    while let Some((current, distance)) = queue.pop_front() {
-        if current == end {
-            return Some(distance);
-        }
-        // println!("Visiting: {:?}", current);
-
-        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-            let nx = current.0.checked_add_signed(dx);
-            let ny = current.1.checked_add_signed(dy);
-            if let (Some(nx), Some(ny)) = (nx, ny) {
-                if nx < MAX_SIZE && ny < MAX_SIZE && !visited[nx][ny] && grid[nx][ny] == 0 {
-                    queue.push_back(((nx, ny), distance + 1));
-                    visited[nx][ny] = true;
-                }
-            }
-        }
-    }
-
-    None
+    Some(path)
 }
 
-fn print_path_on_grid(grid: &Vec<Vec<u32>>, path: Vec<(usize, usize)>) {
-    for (i, row) in grid.iter().enumerate() {
-        for (j, &cell) in row.iter().enumerate() {
-            if path.contains(&(i, j)) {
-                print!("O");
-            } else {
-                print!("{}", if cell == 0 { '.' } else { '#' });
-            }
-        }
-        println!();
-    }
+fn memory_frame(grid: &Grid<bool>, path: Option<&[Point]>) -> Frame {
+    Frame::from_grid(grid, |_, corrupted| if *corrupted { '#' } else { '.' })
+        .path(path.unwrap_or_default().iter().copied(), Color::Green)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let points = parse_input(input);
-    let grid = create_grid(&points, MAX_BYTES);
-    println!("================");
+/// Minimum number of steps to the exit of a `size` x `size` memory space after `bytes` bytes have fallen.
+fn min_steps(input: &str, size: usize, bytes: usize) -> Result<Option<u32>, ParseError> {
+    let points = parse_input(input)?;
+    let grid = create_grid(&points, size, bytes);
 
-    let start = (0, 0);
-    let end = (MAX_SIZE - 1, MAX_SIZE - 1);
-    let distance = smallest_path(&grid, start, end)?;
+    let start = Point::ZERO;
+    let end = Point::new(size as i32 - 1, size as i32 - 1);
 
-    // println!("Distance: {}", path.len());
-    // print_path_on_grid(&grid, path);
-
-    Some(distance)
-}
+    let path = shortest_path(&grid, start, end);
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let points = parse_input(input);
+    let mut animation = Animation::new();
+    animation.record(|| memory_frame(&grid, path.as_deref()));
+    animation.play();
 
-    let start = (0, 0);
-    let end = (MAX_SIZE - 1, MAX_SIZE - 1);
+    Ok(path.map(|path| path.len() as u32 - 1))
+}
 
-    let mut low: usize = MAX_BYTES;
-    let mut high: usize = points.len();
+pub fn part_one(input: &str) -> Result<Option<u32>, ParseError> {
+    min_steps(input, MAX_SIZE, MAX_BYTES)
+}
 
-    while low < high {
-        let mid = (low + high) / 2;
-        let new_points = points[0..mid].to_vec();
-        let grid = create_grid(&new_points, mid);
+/// The first byte that cuts off the exit of a `size` x `size` memory space, as `x,y`.
+///
+/// The exit is cut off as soon as corrupted bytes, touching each other horizontally, vertically or
+/// diagonally, form a wall from the top or right edge to the bottom or left edge. Adds the bytes one
+/// by one to a union-find with one extra set for each of the two sides, until the sides join.
+fn first_blocking_byte(input: &str, size: usize) -> Result<Option<String>, ParseError> {
+    let points = parse_input(input)?;
+
+    let mut grid = Grid::new(size, size, false);
+    let top_right = grid.len();
+    let bottom_left = grid.len() + 1;
+    let mut walls = UnionFind::new(grid.len() + 2);
+
+    let last = size as i32 - 1;
+    for (line, &p) in points.iter().enumerate() {
+        let Some(i) = grid.index_of(p) else {
+            return Err(ParseError {
+                line: line + 1,
+                column: 1,
+                message: format!("byte {p} is outside the {size}x{size} memory space"),
+            });
+        };
+        grid[p] = true;
+
+        if p.y == 0 || p.x == last {
+            walls.union(i, top_right);
+        }
+        if p.x == 0 || p.y == last {
+            walls.union(i, bottom_left);
+        }
+        for n in grid.neighbors8(p).filter(|n| grid[*n]) {
+            walls.union(i, grid.index_of(n).unwrap());
+        }
 
-        if let Some(distance) = smallest_path(&grid, start, end) {
-            low = mid + 1;
-        } else {
-            high = mid;
+        if walls.same_set(top_right, bottom_left) {
+            let mut animation = Animation::new();
+            animation.record(|| {
+                let wall = grid
+                    .points()
+                    .filter(|q| grid[*q] && walls.same_set(grid.index_of(*q).unwrap(), top_right));
+                memory_frame(&grid, None)
+                    .highlight(wall, Color::Yellow)
+                    .mark(p, '#', Color::Red)
+                    .with_caption(format!("byte {p} closes the wall"))
+            });
+            animation.play();
+
+            return Ok(Some(p.to_string()));
         }
     }
 
-    println!("{:?}", points[high - 1]);
-    None
+    Ok(None)
+}
+
+pub fn part_two(input: &str) -> Result<Option<String>, ParseError> {
+    first_blocking_byte(input, MAX_SIZE)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(22));
-    }
+    advent_of_code::example_tests!();
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+    fn byte_outside_memory_is_an_error() {
+        let error = first_blocking_byte("1,1\n2,5\n", 5).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.message, "byte 2,5 is outside the 5x5 memory space");
     }
 }
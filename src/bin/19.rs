@@ -1,5 +1,6 @@
 advent_of_code::solution!(19);
 
+use advent_of_code::log;
 use std::collections::HashMap;
 
 fn parse_input(input: &str) -> (Vec<String>, Vec<String>) {
@@ -96,7 +97,7 @@ pub fn part_two(input: &str) -> Option<u64> {
 
     for combination in combinations {
         let combination_count = count_combinations(&patterns_vec, &mut patterns_map, &combination);
-        println!("{} -> {}", combination, combination_count);
+        log::debug!("{combination} -> {combination_count}");
         answer += combination_count;
 
         // break;
@@ -114,15 +115,5 @@ pub fn part_two(input: &str) -> Option<u64> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(16));
-    }
+    advent_of_code::example_tests!();
 }
@@ -1,158 +1,113 @@
 advent_of_code::solution!(20);
 
-use std::collections::HashMap;
-use std::collections::VecDeque;
+use advent_of_code::grid::{Grid, Point};
+use advent_of_code::log;
+use advent_of_code::pathfinding;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
 
 const TIME_LIMIT: usize = 100;
-// const TIME_LIMIT: usize = 75;
 
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
-}
+/// Distance from the start to every cell of the race track, `None` for walls.
+fn track_distances(input: &str) -> Grid<Option<usize>> {
+    let grid = Grid::parse(input);
 
-fn get_coords(grid: &Vec<Vec<char>>, c: char) -> Option<(usize, usize)> {
-    for (i, row) in grid.iter().enumerate() {
-        for (j, &cell) in row.iter().enumerate() {
-            if cell == c {
-                return Some((i, j));
-            }
-        }
+    let start = grid.find(&'S').unwrap();
+
+    let mut distances = Grid::new(grid.width(), grid.height(), None);
+    let reached =
+        pathfinding::bfs_distances(start, |p| grid.neighbors4(*p).filter(|n| grid[*n] != '#'));
+    for (p, distance) in reached {
+        distances[p] = Some(distance);
     }
-    None
+    distances
 }
 
-fn bfs(
-    grid: &Vec<Vec<char>>,
-    start: (usize, usize),
-    end: (usize, usize),
-) -> (HashMap<(usize, usize), usize>, Vec<(usize, usize)>) {
-    let mut queue = VecDeque::new();
-    queue.push_back(start);
-    let mut distance = HashMap::new();
-    distance.insert(start, 0);
-
-    let mut path = Vec::new();
-    path.push(start);
-
-    while let Some(current) = queue.pop_front() {
-        if current == end {
-            return (distance, path);
-        }
+/// How many cheats save how many picoseconds, listed like in the puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Savings(BTreeMap<usize, u32>);
 
-        for direction in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let next = (
-                (current.0 as isize + direction.0) as usize,
-                (current.1 as isize + direction.1) as usize,
-            );
-            if next.0 < 0 || next.1 < 0 || next.0 >= grid.len() || next.1 >= grid[0].len() {
-                continue;
-            }
-            if grid[next.0][next.1] == '#' {
-                continue;
-            }
-            if distance.contains_key(&next) && distance[&next] <= distance[&current] + 1 {
-                continue;
-            }
-            distance.insert(next, distance[&current] + 1);
-            path.push(next);
-            queue.push_back(next);
-        }
+impl Savings {
+    /// Number of cheats.
+    fn total(&self) -> u32 {
+        self.0.values().sum()
     }
-
-    (distance, path)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let grid = parse_input(input);
-
-    let start = get_coords(&grid, 'S').unwrap();
-    let end = get_coords(&grid, 'E').unwrap();
-
-    let (start_distance, _) = bfs(&grid, start, end);
-
-    let mut answer = 0;
-
-    for (k, v) in &start_distance {
-        let (x, y) = *k;
-
-        for direction in [(-2, 0), (2, 0), (0, -2), (0, 2)] {
-            let next = (
-                (x as isize + direction.0) as usize,
-                (y as isize + direction.1) as usize,
-            );
-            if next.0 < 0
-                || next.1 < 0
-                || next.0 >= grid.len()
-                || next.1 >= grid[0].len()
-                || !start_distance.contains_key(&next)
-            {
-                continue;
+impl Display for Savings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (saving, count) in &self.0 {
+            match count {
+                1 => writeln!(f, "There is one cheat that saves {saving} picoseconds.")?,
+                _ => writeln!(
+                    f,
+                    "There are {count} cheats that save {saving} picoseconds."
+                )?,
             }
+        }
+        Ok(())
+    }
+}
 
-            // println!("{} {}", start_distance[&next], *v);
-
-            if start_distance[&next] as i32 - *v as i32 >= (TIME_LIMIT + 2) as i32 {
-                answer += 1;
+/// The savings of all cheats of up to `max_cheat` picoseconds that save at least `min_saving` picoseconds.
+///
+/// A cheat goes from one track cell to another within `max_cheat` steps, ignoring walls, and saves
+/// the difference of their distances along the track minus its own length.
+fn cheat_savings(input: &str, max_cheat: usize, min_saving: usize) -> Savings {
+    let distances = track_distances(input);
+    let max_cheat = max_cheat as i32;
+
+    let mut savings = Savings::default();
+    for (from, from_distance) in distances.iter() {
+        let Some(from_distance) = *from_distance else {
+            continue;
+        };
+
+        for dy in -max_cheat..=max_cheat {
+            let reach = max_cheat - dy.abs();
+            for dx in -reach..=reach {
+                let to = from + Point::new(dx, dy);
+                let Some(&Some(to_distance)) = distances.get(to) else {
+                    continue;
+                };
+
+                let cheat = from.manhattan(to) as usize;
+                let saving = to_distance.saturating_sub(from_distance + cheat);
+                if saving > 0 && saving >= min_saving {
+                    *savings.0.entry(saving).or_default() += 1;
+                }
             }
         }
     }
 
-    Some(answer)
+    savings
 }
 
-fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
-}
+/// Number of cheats of up to `max_cheat` picoseconds that save at least `min_saving` picoseconds.
+/// The savings are listed with `--verbose`.
+fn count_cheats(input: &str, max_cheat: usize, min_saving: usize) -> Option<u32> {
+    let savings = cheat_savings(input, max_cheat, min_saving);
+    log::debug!("cheats of up to {max_cheat} picoseconds:\n{savings}");
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let grid = parse_input(input);
-
-    let start = get_coords(&grid, 'S').unwrap();
-    let end = get_coords(&grid, 'E').unwrap();
-
-    let (start_distance, _) = bfs(&grid, start, end);
-    // let (end_distance, _) = bfs(&grid, end, start);
-
-    let mut answer = 0;
-
-    let mut points: Vec<(usize, usize)> = start_distance.keys().cloned().collect();
-    points.sort_by_key(|point| start_distance[point]);
-
-    for i in 0..points.len() {
-        for j in i + 1..points.len() {
-            let point_a = points[i];
-            let point_b = points[j];
-
-            let dist_a = start_distance[&point_a];
-            let dist_b = start_distance[&point_b];
+    Some(savings.total())
+}
 
-            let diff = manhattan_distance(point_a, point_b);
-            if diff <= 20 && (dist_b as i32 - dist_a as i32 - diff as i32) >= TIME_LIMIT as i32 {
-                answer += 1;
-                // println!(
-                //     "({},{}) -> ({},{})",
-                //     point_a.0, point_a.1, point_b.0, point_b.1
-                // );
-            }
-        }
-    }
+pub fn part_one(input: &str) -> Option<u32> {
+    count_cheats(input, 2, TIME_LIMIT)
+}
 
-    Some(answer)
+pub fn part_two(input: &str) -> Option<u32> {
+    count_cheats(input, 20, TIME_LIMIT)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(1)); // 50s
-    }
+    advent_of_code::example_tests!();
 
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3)); // 75s
+    /// The listing of [`cheat_savings`], compared with the puzzle's by the example tests.
+    fn savings_listing(input: &str, max_cheat: usize, min_saving: usize) -> String {
+        cheat_savings(input, max_cheat, min_saving).to_string()
     }
 }
@@ -1,193 +1,162 @@
-advent_of_code::solution!(21);
+advent_of_code::solution!(21, parse = parse_input);
 
-use lazy_static::lazy_static;
+use advent_of_code::grid::{Direction, Grid, Point};
+use advent_of_code::parse::{ParseError, Parser};
+use advent_of_code::pathfinding;
 use std::collections::HashMap;
 
-lazy_static! {
-    static ref NUMERIC: HashMap<char, (usize, usize)> = HashMap::from([
-        ('7', (0, 0)),
-        ('8', (0, 1)),
-        ('9', (0, 2)),
-        ('4', (1, 0)),
-        ('5', (1, 1)),
-        ('6', (1, 2)),
-        ('1', (2, 0)),
-        ('2', (2, 1)),
-        ('3', (2, 2)),
-        ('0', (3, 1)),
-        ('A', (3, 2)),
-    ]);
-    static ref DIRECTIONAL: HashMap<char, (usize, usize)> = HashMap::from([
-        ('^', (0, 1)),
-        ('A', (0, 2)),
-        ('<', (1, 0)),
-        ('v', (1, 1)),
-        ('>', (1, 2)),
-    ]);
-}
+/// The door's numeric keypad, with a gap in the bottom left corner.
+const NUMERIC: &str = "789\n456\n123\n 0A";
+/// The directional keypad of the robots, with a gap in the top left corner.
+const DIRECTIONAL: &str = " ^A\n<v>";
 
-fn parse_input(input: &str) -> Vec<String> {
-    input.lines().map(|line| line.trim().to_string()).collect()
+/// A keypad layout with one row per line and a space for every gap. Arms start on `A`.
+#[derive(Debug, Clone)]
+pub struct Keypad {
+    grid: Grid<char>,
 }
 
-fn find_codes() -> (HashMap<(char, char), String>, HashMap<(char, char), String>) {
-    let mut numeric_codes = HashMap::new();
-    let mut directional_codes = HashMap::new();
-
-    for (prev, (x, y)) in NUMERIC.iter() {
-        for (next, (nx, ny)) in NUMERIC.iter() {
-            let mut path = "<".repeat(y.saturating_sub(*ny))
-                + &"v".repeat(nx.saturating_sub(*x))
-                + &"^".repeat(x.saturating_sub(*nx))
-                + &">".repeat(ny.saturating_sub(*y));
-            if (*x, *ny) == (3, 0) || (*nx, *y) == (3, 0) {
-                path = path.chars().rev().collect();
-            }
-            numeric_codes.insert((*prev, *next), path + "A");
+impl Keypad {
+    pub fn new(layout: &str) -> Self {
+        Self {
+            grid: Grid::parse(layout),
         }
     }
 
-    for (prev, (x, y)) in DIRECTIONAL.iter() {
-        for (next, (nx, ny)) in DIRECTIONAL.iter() {
-            let mut path = "<".repeat(y.saturating_sub(*ny))
-                + &"v".repeat(nx.saturating_sub(*x))
-                + &"^".repeat(x.saturating_sub(*nx))
-                + &">".repeat(ny.saturating_sub(*y));
-            if (*nx, *y) == (0, 0) || (*x, *ny) == (0, 0) {
-                path = path.chars().rev().collect();
-            }
-            directional_codes.insert((*prev, *next), path + "A");
-        }
+    fn keys(&self) -> impl Iterator<Item = (Point, char)> + '_ {
+        self.grid
+            .iter()
+            .filter(|(_, key)| **key != ' ')
+            .map(|(p, key)| (p, *key))
     }
 
-    (numeric_codes, directional_codes)
-}
+    fn position(&self, key: char) -> Option<Point> {
+        self.grid.find(&key)
+    }
 
-fn numeric_code_to_directional_code(
-    code: &str,
-    numeric_codes: &HashMap<(char, char), String>,
-) -> String {
-    let mut result = String::new();
-    let mut prev = 'A';
-    for c in code.chars() {
-        result.push_str(&numeric_codes[&(prev, c)]);
-        prev = c;
+    /// Whether an arm can point at `p`, arms panic over gaps.
+    fn is_key(&self, p: Point) -> bool {
+        self.grid.get(p).is_some_and(|key| *key != ' ')
     }
-    result
 }
 
-fn directional_to_directional_code(
-    code: &str,
-    directional_codes: &HashMap<(char, char), String>,
-) -> String {
-    let mut result = String::new();
-    let mut prev = 'A';
-    for c in code.chars() {
-        result.push_str(&directional_codes[&(prev, c)]);
-        prev = c;
+/// Presses on the keypad in front of you to make an arm move from one key to another and press it.
+type Costs = HashMap<(char, char), u64>;
+
+/// The costs of an arm on `keypad`, steered by a robot whose own keypad `controller` costs `controller_costs`.
+///
+/// The arm moves one key per press on `controller` and presses its key with the controller's `A`.
+/// The cheapest way between two keys is a search over the arm's position and the key the
+/// controller points at, which also finds detours around gaps.
+fn press_costs(keypad: &Keypad, controller: &Keypad, controller_costs: &Costs) -> Costs {
+    let mut costs = Costs::new();
+
+    for (from_position, from) in keypad.keys() {
+        let distances = pathfinding::dijkstra_distances((from_position, 'A'), |&(p, held)| {
+            Direction::ALL
+                .into_iter()
+                .filter(|direction| keypad.is_key(p.step(*direction)))
+                .map(|direction| {
+                    let arrow = direction.arrow();
+                    ((p.step(direction), arrow), controller_costs[&(held, arrow)])
+                })
+                .collect::<Vec<_>>()
+        });
+
+        for (to_position, to) in keypad.keys() {
+            let cost = controller
+                .keys()
+                .filter_map(|(_, held)| {
+                    let moves = distances.get(&(to_position, held))?;
+                    Some(moves + controller_costs[&(held, 'A')])
+                })
+                .min();
+            if let Some(cost) = cost {
+                costs.insert((from, to), cost);
+            }
+        }
     }
-    result
-}
 
-// Add this new struct to store memoization results
-#[derive(Default)]
-struct Memo {
-    cache: HashMap<(String, u32, bool), u128>,
+    costs
 }
 
-impl Memo {
-    fn get_length(
-        &mut self,
-        sequence: &str,
-        iterations: u32,
-        first_iter: bool,
-        numeric_codes: &HashMap<(char, char), String>,
-        directional_codes: &HashMap<(char, char), String>,
-    ) -> u128 {
-        // Check cache first
-        let key = (sequence.to_string(), iterations, first_iter);
-        if let Some(&result) = self.cache.get(&key) {
-            return result;
-        }
-
-        // Base case
-        if iterations == 0 {
-            return sequence.len() as u128;
-        }
+/// The costs of the arm on `door`, at the end of a chain of `robots` robots on `remote` keypads
+/// plus the `remote` keypad in front of you.
+fn chain_costs(door: &Keypad, remote: &Keypad, robots: usize) -> Costs {
+    // pressing a key on your own keypad takes exactly one press
+    let mut costs: Costs = remote
+        .keys()
+        .flat_map(|(_, a)| remote.keys().map(move |(_, b)| ((a, b), 1)))
+        .collect();
+
+    for _ in 0..robots {
+        costs = press_costs(remote, remote, &costs);
+    }
 
-        // Recursive case
-        let mut total_length = 0;
-        let mut prev = 'A';
-        let codes = if first_iter {
-            numeric_codes
-        } else {
-            directional_codes
-        };
-
-        for c in sequence.chars() {
-            let next_sequence = &codes[&(prev, c)];
-            total_length += self.get_length(
-                next_sequence,
-                iterations - 1,
-                false,
-                numeric_codes,
-                directional_codes,
-            );
-            prev = c;
-        }
+    press_costs(door, remote, &costs)
+}
 
-        // Store in cache and return
-        self.cache.insert(key, total_length);
-        total_length
-    }
+/// A door code like `029A` and its numeric part.
+struct Code {
+    sequence: String,
+    number: u64,
 }
 
-pub fn part_one(input: &str) -> Option<u128> {
-    let lines = parse_input(input);
-    let (numeric_codes, directional_codes) = find_codes();
-    let mut memo = Memo::default();
+/// The door codes to type.
+pub struct Puzzle {
+    codes: Vec<Code>,
+}
 
-    let mut complexity: u128 = 0;
-    for line in lines {
-        let number = line[..line.len() - 1].parse::<u128>().unwrap();
-        complexity += number * memo.get_length(&line, 3, true, &numeric_codes, &directional_codes);
-    }
+fn parse_input(input: &str) -> Result<Puzzle, ParseError> {
+    let codes = Parser::new(input).lines(|p| {
+        let sequence = p.rest().to_string();
+        let number = p.integer()?;
+        p.literal("A")?;
+        Ok(Code { sequence, number })
+    })?;
 
-    Some(complexity)
+    Ok(Puzzle { codes })
 }
 
-pub fn part_two(input: &str) -> Option<u128> {
-    let lines = parse_input(input);
-    let (numeric_codes, directional_codes) = find_codes();
-    let mut memo = Memo::default();
+/// Sum of the presses of every code times its number, through `robots` robots on directional
+/// keypads, with the given layouts of the door keypad and the directional keypads.
+fn complexity_with(puzzle: &Puzzle, door: &str, remote: &str, robots: usize) -> Option<u64> {
+    let (door, remote) = (Keypad::new(door), Keypad::new(remote));
+    door.position('A')?;
+    remote.position('A')?;
+
+    let costs = chain_costs(&door, &remote, robots);
+
+    puzzle
+        .codes
+        .iter()
+        .map(|code| {
+            let keys = code.sequence.chars();
+            let presses = std::iter::once('A')
+                .chain(keys.clone())
+                .zip(keys)
+                .map(|pair| costs.get(&pair))
+                .sum::<Option<u64>>()?;
+            Some(code.number * presses)
+        })
+        .sum()
+}
 
-    let mut complexity: u128 = 0;
-    for line in lines {
-        let number = line[..line.len() - 1].parse::<u128>().unwrap();
-        complexity += number * memo.get_length(&line, 26, true, &numeric_codes, &directional_codes);
-    }
+fn complexity(puzzle: &Puzzle, robots: usize) -> Option<u64> {
+    complexity_with(puzzle, NUMERIC, DIRECTIONAL, robots)
+}
 
-    println!("--------------------------------");
-    println!("{}", complexity);
-    println!("--------------------------------");
+pub fn part_one(puzzle: &Puzzle) -> Option<u64> {
+    complexity(puzzle, 2)
+}
 
-    // Some(complexity)
-    None
+pub fn part_two(puzzle: &Puzzle) -> Option<u64> {
+    complexity(puzzle, 25)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(126384));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
-    }
+    advent_of_code::example_tests!();
 }
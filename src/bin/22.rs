@@ -1,84 +1,87 @@
 advent_of_code::solution!(22);
 
-use std::collections::{HashMap, HashSet};
-
-// Hint from reddit: The problem is based on the linear feedback shift register
-// https://en.wikipedia.org/wiki/Linear_feedback_shift_register
-/// [Xorshift LFSR](https://en.wikipedia.org/wiki/Linear-feedback_shift_register#Xorshift_LFSRs).
-fn hash(mut n: usize) -> usize {
-    n = (n ^ (n << 6)) & 0xffffff;
-    n = (n ^ (n >> 5)) & 0xffffff;
-    (n ^ (n << 11)) & 0xffffff
-}
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::xorshift;
+use std::thread;
 
-fn get_2k_number(n: usize) -> usize {
-    let mut number = n;
-    for _ in 0..2000 {
-        number = hash(number);
-    }
-    number
-}
+const STEPS: usize = 2000;
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut numbers = Vec::new();
+/// Price changes lie in `-9..=9`, so a window of four of them is a 4 digit number in base 19.
+const CHANGES: usize = 19;
+const WINDOWS: usize = CHANGES.pow(4);
 
-    for line in input.lines() {
-        numbers.push(line.parse::<usize>().unwrap());
-    }
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse::integers(input)
+}
 
-    let mut answer = 0;
+/// Steps all buyers' numbers side by side, which vectorizes.
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let mut numbers = parse_input(input)?;
 
-    for number in numbers {
-        let n = get_2k_number(number);
-        answer += n;
-        // println!("{}", n);
+    for _ in 0..STEPS {
+        xorshift::step_all(&mut numbers);
     }
 
-    Some(answer)
+    Ok(numbers.iter().map(|n| u64::from(*n)).sum())
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let numbers: Vec<usize> = input.lines().map(|line| line.parse().unwrap()).collect();
-
-    let mut total_bananas: HashMap<Vec<i32>, u64> = HashMap::new();
+/// Adds the bananas each window of four price changes buys from `buyers` to `totals`.
+/// Only the first time a buyer shows a window counts, tracked with a stamp per window.
+fn add_bananas(buyers: &[u32], totals: &mut [u32]) {
+    let mut seen = vec![0u32; WINDOWS];
 
-    for &num in &numbers {
-        let mut seen = HashSet::new();
-        let mut diffs = Vec::new();
-        let mut current = num;
+    for (stamp, &seed) in (1..).zip(buyers) {
+        let mut window = 0;
+        let mut price = seed % 10;
 
-        for i in 0..2000 {
-            let next = hash(current);
-            diffs.push(((next % 10) as i32) - ((current % 10) as i32));
-            current = next;
+        for (i, next) in xorshift::secrets(seed).take(STEPS).enumerate() {
+            let next_price = next % 10;
+            let change = (next_price + 9 - price) as usize;
+            window = (window * CHANGES + change) % WINDOWS;
+            price = next_price;
 
-            if i >= 3 {
-                let sequence = diffs.clone();
-                if !seen.contains(&sequence) {
-                    seen.insert(sequence.clone());
-                    *total_bananas.entry(sequence).or_default() += (current % 10) as u64;
-                }
-                diffs.remove(0);
+            if i >= 3 && seen[window] != stamp {
+                seen[window] = stamp;
+                totals[window] += price;
             }
         }
     }
+}
 
-    total_bananas.values().max().copied()
+/// Splits the buyers across threads, each summing up the bananas per window on its own.
+pub fn part_two(input: &str) -> Result<Option<u32>, ParseError> {
+    let buyers = parse_input(input)?;
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = buyers.len().div_ceil(threads).max(1);
+
+    let totals = thread::scope(|scope| {
+        let workers: Vec<_> = buyers
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut totals = vec![0u32; WINDOWS];
+                    add_bananas(chunk, &mut totals);
+                    totals
+                })
+            })
+            .collect();
+
+        let mut totals = vec![0u32; WINDOWS];
+        for worker in workers {
+            for (total, bananas) in totals.iter_mut().zip(worker.join().unwrap()) {
+                *total += bananas;
+            }
+        }
+        totals
+    });
+
+    Ok(totals.into_iter().max())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(37327623));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(24));
-    }
+    advent_of_code::example_tests!();
 }
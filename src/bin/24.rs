@@ -1,168 +1,503 @@
-advent_of_code::solution!(24);
+advent_of_code::solution!(24, parse = Circuit::parse);
+
+use advent_of_code::export;
+use advent_of_code::graph::{Cycle, Graph, NodeId};
+use advent_of_code::log;
+use advent_of_code::parse::Parser;
+use itertools::Itertools;
 
 use std::collections::HashMap;
-use std::fmt::Display;
+use std::error::Error;
+use std::fmt::{Display, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
 
-#[derive(Debug, Clone)]
-enum Op {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
     And,
     Or,
     Xor,
-    Input,
+}
+
+impl Op {
+    fn apply(self, left: bool, right: bool) -> bool {
+        match self {
+            Op::And => left & right,
+            Op::Or => left | right,
+            Op::Xor => left ^ right,
+        }
+    }
+
+    /// Fill color of the gate in the DOT export.
+    fn color(self) -> &'static str {
+        match self {
+            Op::And => "lightblue",
+            Op::Or => "palegreen",
+            Op::Xor => "gold",
+        }
+    }
 }
 
 impl Display for Op {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        let name = match self {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Xor => "XOR",
+        };
+        write!(f, "{name}")
     }
 }
 
-#[derive(Debug)]
-struct Gate {
-    name: String,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gate {
     op: Op,
-    left: Option<String>,
-    right: Option<String>,
-    output: Option<u8>,
+    inputs: [String; 2],
+    output: String,
 }
 
 impl Gate {
-    fn new(
-        name: String,
-        op: Op,
-        left: Option<String>,
-        right: Option<String>,
-        output: Option<u8>,
-    ) -> Self {
-        Self {
-            name,
-            op,
-            left,
-            right,
-            output,
-        }
+    fn has_input(&self, wire: &str) -> bool {
+        self.inputs.iter().any(|input| input == wire)
     }
-}
 
-fn evaluate(gates: &mut HashMap<String, Gate>, name: &str) -> u8 {
-    println!("Evaluating {}", name);
-    println!("{:?}", gates.get(name).unwrap());
-
-    if let Some(output) = gates.get(name).unwrap().output {
-        return output;
+    /// Both inputs are bits of `x` and `y`.
+    fn reads_inputs(&self) -> bool {
+        self.inputs.iter().all(|input| is_input_bit(input))
     }
 
-    let (op, left_name, right_name) = {
-        let gate = gates.get(name).unwrap();
-        (
-            gate.op.clone(),
-            gate.left.as_ref().unwrap().clone(),
-            gate.right.as_ref().unwrap().clone(),
-        )
-    };
-    let left = evaluate(gates, &left_name);
-    let right = evaluate(gates, &right_name);
+    fn reads_first_bit(&self) -> bool {
+        self.has_input("x00") || self.has_input("y00")
+    }
+}
 
-    println!("{} {} {}", left, right, op);
+fn is_input_bit(wire: &str) -> bool {
+    wire.starts_with('x') || wire.starts_with('y')
+}
 
-    let result = match op {
-        Op::And => left & right,
-        Op::Or => left | right,
-        Op::Xor => left ^ right,
-        Op::Input => panic!("Input gate should not be evaluated"),
-    };
+fn bit_name(prefix: char, bit: usize) -> String {
+    format!("{prefix}{bit:02}")
+}
 
-    gates.get_mut(name).unwrap().output = Some(result);
-    result
+/// The gates as a network of wires, evaluated in topological order.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    gates: Vec<Gate>,
+    initial: Vec<(String, bool)>,
+    /// Wires as nodes, with edges from the inputs of every gate to its output.
+    wires: Graph<String>,
+    /// Wire ids of the inputs and output of every gate, in evaluation order.
+    order: Vec<([NodeId; 2], Op, NodeId)>,
+    x: Vec<NodeId>,
+    y: Vec<NodeId>,
+    z: Vec<NodeId>,
 }
 
-fn parse_input(input: &str) -> HashMap<String, Gate> {
-    let mut gates = HashMap::new();
-    for line in input.lines() {
-        if let Some((input, output)) = line.split_once("->") {
-            let name = output.trim().to_string();
-            let gate = if input.contains("AND") {
-                let (left, right) = input.split_once("AND").unwrap();
-                Gate::new(
-                    name.clone(),
-                    Op::And,
-                    Some(left.trim().to_string()),
-                    Some(right.trim().to_string()),
-                    None,
-                )
-            } else if input.contains("XOR") {
-                let (left, right) = input.split_once("XOR").unwrap();
-                Gate::new(
-                    name.clone(),
-                    Op::Xor,
-                    Some(left.trim().to_string()),
-                    Some(right.trim().to_string()),
-                    None,
-                )
-            } else if input.contains("OR") {
-                let (left, right) = input.split_once("OR").unwrap();
-                Gate::new(
-                    name.clone(),
-                    Op::Or,
-                    Some(left.trim().to_string()),
-                    Some(right.trim().to_string()),
-                    None,
+impl Circuit {
+    /// Wires up `gates`, failing if their outputs feed back into their own inputs.
+    fn new(gates: Vec<Gate>, initial: Vec<(String, bool)>) -> Result<Self, Cycle<String>> {
+        let mut wires = Graph::directed();
+        for (wire, _) in &initial {
+            wires.add_node(wire.clone());
+        }
+        for gate in &gates {
+            for input in &gate.inputs {
+                wires.add_edge(input.clone(), gate.output.clone());
+            }
+        }
+
+        let mut driver = vec![None; wires.len()];
+        for (i, gate) in gates.iter().enumerate() {
+            driver[wires.id(&gate.output).unwrap()] = Some(i);
+        }
+        let order = wires
+            .toposort()?
+            .into_iter()
+            .filter_map(|wire| driver[wire])
+            .map(|i| {
+                let gate: &Gate = &gates[i];
+                let input = |n: usize| wires.id(&gate.inputs[n]).unwrap();
+                (
+                    [input(0), input(1)],
+                    gate.op,
+                    wires.id(&gate.output).unwrap(),
                 )
+            })
+            .collect();
+
+        let bits = |prefix: char| -> Vec<NodeId> {
+            (0..)
+                .map_while(|bit| wires.id(&bit_name(prefix, bit)))
+                .collect()
+        };
+        let (x, y, z) = (bits('x'), bits('y'), bits('z'));
+
+        Ok(Self {
+            gates,
+            initial,
+            wires,
+            order,
+            x,
+            y,
+            z,
+        })
+    }
+
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let mut initial = vec![];
+        let mut gates = vec![];
+
+        Parser::new(input).lines(|p| {
+            let first = p.word()?.to_string();
+            if p.literal(": ").is_ok() {
+                let value: u8 = p.integer()?;
+                initial.push((first, value == 1));
+                return Ok(());
+            }
+
+            p.spaces();
+            let op = match p.word()? {
+                "AND" => Op::And,
+                "OR" => Op::Or,
+                "XOR" => Op::Xor,
+                op => return Err(p.error(format!("unknown gate {op}"))),
+            };
+            p.spaces();
+            let second = p.word()?.to_string();
+            p.literal(" -> ")?;
+            let output = p.word()?.to_string();
+            if gates.iter().any(|gate: &Gate| gate.output == output) {
+                return Err(p.error(format!("{output} is the output of two gates")));
+            }
+            gates.push(Gate {
+                op,
+                inputs: [first, second],
+                output,
+            });
+            Ok(())
+        })?;
+
+        let circuit = Self::new(gates, initial)?;
+        // numbers are read into a u64, which needs a bit left over for the carry of a sum.
+        if circuit.x.len().max(circuit.y.len()) >= u64::BITS as usize
+            || circuit.z.len() > u64::BITS as usize
+        {
+            return Err(format!(
+                "circuits with more than {} input bits are not supported",
+                u64::BITS - 1
+            )
+            .into());
+        }
+        Ok(circuit)
+    }
+
+    /// Number of bits of `x` and `y`.
+    pub fn input_bits(&self) -> usize {
+        self.x.len().min(self.y.len())
+    }
+
+    /// Runs all gates on the input wires set in `values`, indexed by wire id.
+    fn run(&self, values: &mut [bool]) {
+        for &([left, right], op, output) in &self.order {
+            values[output] = op.apply(values[left], values[right]);
+        }
+    }
+
+    fn read(values: &[bool], bits: &[NodeId]) -> u64 {
+        bits.iter()
+            .enumerate()
+            .map(|(bit, wire)| u64::from(values[*wire]) << bit)
+            .sum()
+    }
+
+    /// The number on the `z` wires for the initial values of the input.
+    pub fn output(&self) -> u64 {
+        let mut values = vec![false; self.wires.len()];
+        for (wire, value) in &self.initial {
+            values[self.wires.id(wire).unwrap()] = *value;
+        }
+        self.run(&mut values);
+        Self::read(&values, &self.z)
+    }
+
+    /// The number on the `z` wires with `x` and `y` on the input wires.
+    pub fn simulate(&self, x: u64, y: u64) -> u64 {
+        let mut values = vec![false; self.wires.len()];
+        for (bits, number) in [(&self.x, x), (&self.y, y)] {
+            for (bit, wire) in bits.iter().enumerate() {
+                values[*wire] = number >> bit & 1 == 1;
+            }
+        }
+        self.run(&mut values);
+        Self::read(&values, &self.z)
+    }
+
+    /// The bits at which the circuit does not add like a ripple-carry adder: adding a single bit,
+    /// carrying out of it, and carrying all the way through it.
+    ///
+    /// Circuits have at most 63 input bits, so the sums fit into a u64.
+    pub fn wrong_bits(&self) -> Vec<usize> {
+        (0..self.input_bits())
+            .filter(|bit| {
+                let below = u64::MAX >> (63 - bit);
+                [
+                    (1 << bit, 0),
+                    (0, 1 << bit),
+                    (1 << bit, 1 << bit),
+                    (below, 1),
+                    (1, below),
+                ]
+                .into_iter()
+                .any(|(x, y)| self.simulate(x, y) != x + y)
+            })
+            .collect()
+    }
+
+    /// A copy of the circuit with the outputs of the gates driving the wires of each pair exchanged.
+    pub fn with_swaps(&self, pairs: &[(&str, &str)]) -> Result<Self, Cycle<String>> {
+        let mut gates = self.gates.clone();
+        for (a, b) in pairs {
+            for gate in &mut gates {
+                if gate.output == *a {
+                    gate.output = b.to_string();
+                } else if gate.output == *b {
+                    gate.output = a.to_string();
+                }
+            }
+        }
+        Self::new(gates, self.initial.clone())
+    }
+
+    /// Output wires whose gate breaks the structure of a ripple-carry adder, where every bit past the
+    /// first computes `z = (x ^ y) ^ carry` and `carry' = (x & y) | ((x ^ y) & carry)`, and the last carry is the
+    /// highest bit of `z`.
+    pub fn suspicious_wires(&self) -> Vec<String> {
+        let last_z = bit_name('z', self.input_bits());
+        let feeds = |wire: &str, op: Op| {
+            self.gates
+                .iter()
+                .any(|gate| gate.op == op && gate.has_input(wire))
+        };
+
+        self.gates
+            .iter()
+            .filter(|gate| {
+                let out = gate.output.as_str();
+                let wrong_sum = out.starts_with('z') && out != last_z && gate.op != Op::Xor;
+                let wrong_carry = out == last_z && gate.op != Op::Or;
+                let misplaced_sum =
+                    gate.op == Op::Xor && !gate.reads_inputs() && !out.starts_with('z');
+                let first_bit = gate.reads_first_bit();
+                let unused_half_sum =
+                    gate.op == Op::Xor && gate.reads_inputs() && !first_bit && !feeds(out, Op::Xor);
+                let unused_carry = gate.op == Op::And && !first_bit && !feeds(out, Op::Or);
+                wrong_sum || wrong_carry || misplaced_sum || unused_half_sum || unused_carry
+            })
+            .map(|gate| gate.output.clone())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// The input wires, interleaved by bit as `x00, y00, x01, ...`, followed by any other initial wires.
+    fn input_wires(&self) -> Vec<&str> {
+        let mut wires: Vec<&str> = self.initial.iter().map(|(wire, _)| wire.as_str()).collect();
+        wires.sort_by_key(|wire| {
+            let bit = wire.get(1..).and_then(|bit| bit.parse().ok());
+            (bit.unwrap_or(usize::MAX), *wire)
+        });
+        wires
+    }
+
+    /// The gates reading each wire.
+    fn consumers(&self) -> HashMap<&str, Vec<&str>> {
+        let mut consumers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for gate in &self.gates {
+            for input in &gate.inputs {
+                consumers.entry(input).or_default().push(&gate.output);
+            }
+        }
+        consumers.values_mut().for_each(|outputs| outputs.sort());
+        consumers
+    }
+
+    /// The network in Graphviz DOT, one node per wire named after it. Gates are boxes colored by
+    /// their operation, inputs and outputs are ranked at the sides in order of their bits, and the
+    /// gates driving `highlight` are outlined in red.
+    pub fn to_dot(&self, highlight: &[String]) -> String {
+        let mut dot = String::new();
+        let chain = |wires: &[&str]| wires.iter().map(|wire| format!("{wire:?}")).join(" -> ");
+        let outputs: Vec<&str> = self
+            .z
+            .iter()
+            .map(|id| self.wires.name(*id).as_str())
+            .collect();
+        let inputs = self.input_wires();
+
+        writeln!(dot, "digraph circuit {{").unwrap();
+        writeln!(dot, "    rankdir=LR;").unwrap();
+        writeln!(
+            dot,
+            "    node [fontname=\"monospace\", style=filled, fillcolor=white];"
+        )
+        .unwrap();
+        for (name, wires) in [("inputs", &inputs), ("outputs", &outputs)] {
+            let rank = if name == "inputs" { "source" } else { "sink" };
+            writeln!(dot, "    subgraph {name} {{").unwrap();
+            writeln!(dot, "        rank={rank};").unwrap();
+            if wires.len() > 1 {
+                writeln!(dot, "        {} [style=invis];", chain(wires)).unwrap();
+            }
+            writeln!(dot, "    }}").unwrap();
+        }
+        for wire in &inputs {
+            writeln!(dot, "    {wire:?} [shape=circle];").unwrap();
+        }
+        for gate in &self.gates {
+            let outline = if highlight.contains(&gate.output) {
+                ", color=red, penwidth=3"
             } else {
-                continue;
+                ""
             };
-            gates.insert(name.clone(), gate);
+            writeln!(
+                dot,
+                "    {:?} [label=\"{}\\n{}\", shape=box, fillcolor={}{outline}];",
+                gate.output,
+                gate.output,
+                gate.op,
+                gate.op.color()
+            )
+            .unwrap();
         }
-        if let Some((input, output)) = line.split_once(":") {
-            let name = input.trim().to_string();
-            let gate = Gate::new(
-                name.clone(),
-                Op::Input,
-                None,
-                None,
-                Some(output.trim().parse().unwrap()),
-            );
-            gates.insert(name, gate);
+        for gate in &self.gates {
+            for input in &gate.inputs {
+                writeln!(dot, "    {input:?} -> {:?};", gate.output).unwrap();
+            }
         }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    /// The network as text, one line per wire sorted by name: the gate driving it, if any, and the
+    /// gates reading it. Wires in `highlight` are marked with `!`.
+    pub fn to_adjacency(&self, highlight: &[String]) -> String {
+        let consumers = self.consumers();
+        let drivers: HashMap<&str, &Gate> = self
+            .gates
+            .iter()
+            .map(|gate| (gate.output.as_str(), gate))
+            .collect();
+
+        let mut text = String::new();
+        for node in self.wires.nodes().sorted_by_key(|id| self.wires.name(*id)) {
+            let wire = self.wires.name(node).as_str();
+            let marker = if highlight.iter().any(|h| h == wire) {
+                "!"
+            } else {
+                ""
+            };
+            write!(text, "{wire}{marker}").unwrap();
+            if let Some(gate) = drivers.get(wire) {
+                write!(text, " = {} {} {}", gate.inputs[0], gate.op, gate.inputs[1]).unwrap();
+            }
+            if let Some(outputs) = consumers.get(wire) {
+                write!(text, " -> {}", outputs.join(" ")).unwrap();
+            }
+            writeln!(text).unwrap();
+        }
+        text
     }
-    gates
-}
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut gates = parse_input(input);
+    /// Writes `circuit.dot` and `circuit.txt` to `dir`, highlighting the wires in `highlight`.
+    pub fn export(&self, dir: &Path, highlight: &[String]) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join("circuit.dot"), self.to_dot(highlight))?;
+        fs::write(dir.join("circuit.txt"), self.to_adjacency(highlight))
+    }
+
+    /// Pairs up the suspicious wires so that swapping each pair turns the circuit into an adder.
+    pub fn repair(&self) -> Option<Vec<(String, String)>> {
+        let suspicious = self.suspicious_wires();
+        let wires: Vec<&str> = suspicious.iter().map(String::as_str).collect();
+        self.find_pairing(&wires, &mut vec![]).map(|pairs| {
+            pairs
+                .into_iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect()
+        })
+    }
 
-    println!("{:?}", gates);
+    fn find_pairing<'a>(
+        &self,
+        wires: &[&'a str],
+        pairs: &mut Vec<(&'a str, &'a str)>,
+    ) -> Option<Vec<(&'a str, &'a str)>> {
+        let Some((first, rest)) = wires.split_first() else {
+            let repaired = self.with_swaps(pairs).ok()?;
+            return repaired.wrong_bits().is_empty().then(|| pairs.clone());
+        };
 
-    let mut ans1 = 0u64;
-    for i in 0..64 {
-        let name = format!("z{:0>2}", i);
-        if !gates.contains_key(&name) {
-            break;
+        for (i, second) in rest.iter().enumerate() {
+            let others: Vec<&str> = rest
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, wire)| *wire)
+                .collect();
+            pairs.push((first, second));
+            if let Some(found) = self.find_pairing(&others, pairs) {
+                return Some(found);
+            }
+            pairs.pop();
         }
-        let bit = evaluate(&mut gates, &name);
-        ans1 |= u64::from(bit) << i;
+        None
     }
+}
 
-    Some(ans1)
+pub fn part_one(circuit: &Circuit) -> Option<u64> {
+    Some(circuit.output())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+/// The swapped wires, sorted and joined with commas.
+///
+/// With `--export <dir>`, also writes the network as `circuit.dot` and `circuit.txt` there, with the
+/// suspicious wires highlighted. Render it with `dot -Tsvg circuit.dot -o circuit.svg`.
+pub fn part_two(circuit: &Circuit) -> Result<String, Box<dyn Error>> {
+    log::debug!("wrong bits before the repair: {:?}", circuit.wrong_bits());
+
+    if let Some(dir) = export::dir() {
+        circuit.export(&dir, &circuit.suspicious_wires())?;
+        log::info!("wrote the circuit to {}", dir.display());
+    }
+
+    let pairs = circuit
+        .repair()
+        .ok_or("no swaps of the suspicious wires turn the circuit into an adder")?;
+    log::info!("swapped pairs: {pairs:?}");
+
+    Ok(pairs
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .sorted()
+        .join(","))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2024));
-    }
+    advent_of_code::example_tests!();
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+    fn rejects_inputs_wider_than_u64() {
+        let wide = |bits: usize| {
+            let mut input: String = (0..bits).map(|bit| format!("x{bit:02}: 1\n")).collect();
+            input += "x00 AND x01 -> z00\n";
+            input
+        };
+        assert!(Circuit::parse(&wide(63)).is_ok());
+        let error = Circuit::parse(&wide(64)).err().unwrap();
+        assert!(error.to_string().contains("more than 63 input bits"));
     }
 }
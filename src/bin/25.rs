@@ -1,5 +1,6 @@
 advent_of_code::solution!(25);
 
+use advent_of_code::parse::{ParseError, Parser};
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -23,50 +24,35 @@ fn is_a_match(lock: &LockOrKey, key: &LockOrKey) -> bool {
     true
 }
 
-fn parse_input(input: &str) -> Vec<LockOrKey> {
-    let mut lines = input.lines();
-    let mut result = Vec::new();
+fn parse_input(input: &str) -> Result<Vec<LockOrKey>, ParseError> {
+    Parser::new(input).sections(|p| {
+        let start = p.clone();
+        let schema = p.lines(|line| Ok(line.take_while(|c| c == '#' || c == '.')))?;
 
-    loop {
-        let mut schema = Vec::new();
-        for _ in 0..7 {
-            let line = lines.next().unwrap();
-            schema.push(line);
+        if schema.len() != 7 || schema.iter().any(|row| row.len() != 5) {
+            return Err(start.error("expected a schematic of 7 rows with 5 columns"));
         }
 
-        let is_lock = schema[0].starts_with("#");
+        let is_lock = schema[0].starts_with('#');
         let mut numeric_schema: [u8; 5] = [0; 5];
 
-        for col in 0..5 {
-            numeric_schema[col] = schema
+        for (col, height) in numeric_schema.iter_mut().enumerate() {
+            *height = schema
                 .iter()
-                .filter(|row| {
-                    if is_lock {
-                        row.chars().nth(col).unwrap() == '#'
-                    } else {
-                        // row.chars().nth(col).unwrap() == '.'
-                        row.chars().nth(col).unwrap() == '#'
-                    }
-                })
+                .filter(|row| row.as_bytes()[col] == b'#')
                 .count() as u8;
         }
 
         if is_lock {
-            result.push(LockOrKey::Lock(numeric_schema));
+            Ok(LockOrKey::Lock(numeric_schema))
         } else {
-            result.push(LockOrKey::Key(numeric_schema));
+            Ok(LockOrKey::Key(numeric_schema))
         }
-
-        if lines.next().is_none() {
-            break;
-        }
-    }
-
-    result
+    })
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let lock_and_keys = parse_input(input);
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let lock_and_keys = parse_input(input)?;
 
     let locks = lock_and_keys
         .iter()
@@ -97,10 +83,10 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
     }
 
-    Some(result)
+    Ok(result)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
 }
 
@@ -108,15 +94,5 @@ pub fn part_two(input: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
-    }
+    advent_of_code::example_tests!();
 }
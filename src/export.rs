@@ -0,0 +1,28 @@
+//! The directory solutions write files to for a closer look, e.g. images of a simulation
+//! ([`crate::image`]) or a graph in DOT format.
+//!
+//! Solutions only export when run with `cargo solve <day> --export <dir>`. Exporting is skipped
+//! while timing with `--time`, so writing files never counts towards a benchmark.
+//!
+//! ```ignore
+//! use advent_of_code::export;
+//!
+//! if let Some(dir) = export::dir() {
+//!     fs::write(dir.join("graph.dot"), graph.to_dot())?;
+//! }
+//! ```
+use std::env;
+use std::path::PathBuf;
+
+/// Returns the directory passed with `--export <dir>`. Never exports while timing.
+pub fn dir() -> Option<PathBuf> {
+    let args: Vec<String> = env::args().collect();
+    if args.iter().any(|x| x == "--time") {
+        return None;
+    }
+
+    args.iter()
+        .position(|x| x == "--export")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
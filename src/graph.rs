@@ -0,0 +1,726 @@
+//! Graphs over interned nodes, e.g. the page ordering rules of day 05 or the LAN party of day 23,
+//! and a [`UnionFind`] for connectivity that grows edge by edge.
+//!
+//! Nodes are added by value and get a dense [`NodeId`], the algorithms work on ids and
+//! [`Graph::name`] maps them back.
+//!
+//! ```ignore
+//! use advent_of_code::graph::Graph;
+//!
+//! let mut graph = Graph::undirected();
+//! for line in input.lines() {
+//!     let (a, b) = line.split_once('-').unwrap();
+//!     graph.add_edge(a, b);
+//! }
+//! let clique = graph.max_clique();
+//! ```
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+pub type NodeId = usize;
+
+/* -------------------------------------------------------------------------- */
+
+/// A fixed size set of small integers, e.g. node ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// An empty set for values below `capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+        }
+    }
+
+    /// Grows the set to hold values below `capacity`.
+    pub fn grow(&mut self, capacity: usize) {
+        let words = capacity.div_ceil(64);
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+    }
+
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let inserted = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        inserted
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, 1 << (value % 64));
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|w| w & (1 << (value % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    /// Size of the intersection without allocating it.
+    pub fn intersection_len(&self, other: &BitSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    fn zip_with(&self, other: &BitSet, f: impl Fn(u64, u64) -> u64) -> BitSet {
+        let len = self.words.len().max(other.words.len());
+        let word = |words: &[u64], i: usize| words.get(i).copied().unwrap_or(0);
+        BitSet {
+            words: (0..len)
+                .map(|i| f(word(&self.words, i), word(&other.words, i)))
+                .collect(),
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new(0);
+        for value in iter {
+            set.grow(value + 1);
+            set.insert(value);
+        }
+        set
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Disjoint sets of the integers below a fixed size, merged with [`UnionFind::union`].
+/// Uses union by size and path halving.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// `len` sets with one element each.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The representative of the set containing `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    /// Merges the sets of `a` and `b`, returning `false` if they were the same set already.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set of `x`.
+    pub fn set_len(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Number of disjoint sets.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A cycle found while sorting a graph topologically, the first node is repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T>(pub Vec<T>);
+
+impl<T: Display> Display for Cycle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle: ")?;
+        for (i, node) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+            write!(f, "{node}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display + fmt::Debug> std::error::Error for Cycle<T> {}
+
+/// A directed or undirected graph without parallel edges.
+#[derive(Debug, Clone)]
+pub struct Graph<T> {
+    directed: bool,
+    ids: HashMap<T, NodeId>,
+    names: Vec<T>,
+    adjacency: Vec<Vec<NodeId>>,
+    edges: Vec<BitSet>,
+}
+
+impl<T: Clone + Eq + Hash> Graph<T> {
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            ids: HashMap::new(),
+            names: vec![],
+            adjacency: vec![],
+            edges: vec![],
+        }
+    }
+
+    /// Returns the id of `node`, adding it if it is new.
+    pub fn add_node(&mut self, node: T) -> NodeId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.ids.insert(node.clone(), id);
+        self.names.push(node);
+        self.adjacency.push(vec![]);
+        self.edges.push(BitSet::new(0));
+        id
+    }
+
+    /// Adds an edge from `a` to `b` (and back for undirected graphs), returning both ids.
+    pub fn add_edge(&mut self, a: T, b: T) -> (NodeId, NodeId) {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        self.connect(a, b);
+        if !self.directed {
+            self.connect(b, a);
+        }
+        (a, b)
+    }
+
+    fn connect(&mut self, a: NodeId, b: NodeId) {
+        self.edges[a].grow(b + 1);
+        if self.edges[a].insert(b) {
+            self.adjacency[a].push(b);
+        }
+    }
+
+    pub fn id(&self, node: &T) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &T {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len()
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> &[NodeId] {
+        &self.adjacency[id]
+    }
+
+    /// The neighbors of `id` as a set.
+    pub fn neighbor_set(&self, id: NodeId) -> &BitSet {
+        &self.edges[id]
+    }
+
+    pub fn has_edge(&self, a: NodeId, b: NodeId) -> bool {
+        self.edges[a].contains(b)
+    }
+
+    /// Sorts all nodes so that every edge points forward.
+    pub fn toposort(&self) -> Result<Vec<NodeId>, Cycle<T>> {
+        self.toposort_of(&self.nodes().collect::<Vec<_>>())
+    }
+
+    /// Sorts `nodes` so that every edge between them points forward, ignoring all other nodes.
+    pub fn toposort_of(&self, nodes: &[NodeId]) -> Result<Vec<NodeId>, Cycle<T>> {
+        let subset: BitSet = nodes.iter().copied().collect();
+        let mut in_degree = vec![0; self.len()];
+
+        for &a in nodes {
+            for &b in &self.adjacency[a] {
+                if subset.contains(b) {
+                    in_degree[b] += 1;
+                }
+            }
+        }
+
+        let mut queue: VecDeque<NodeId> = nodes
+            .iter()
+            .copied()
+            .filter(|n| in_degree[*n] == 0)
+            .collect();
+        let mut sorted = Vec::with_capacity(nodes.len());
+
+        while let Some(a) = queue.pop_front() {
+            sorted.push(a);
+            for &b in &self.adjacency[a] {
+                if subset.contains(b) {
+                    in_degree[b] -= 1;
+                    if in_degree[b] == 0 {
+                        queue.push_back(b);
+                    }
+                }
+            }
+        }
+
+        if sorted.len() == nodes.len() {
+            return Ok(sorted);
+        }
+
+        // every node left over has a predecessor that is left over as well,
+        // walking them backwards has to run into a cycle.
+        let left_over = |n: &NodeId| subset.contains(*n) && in_degree[*n] > 0;
+        let mut walk = vec![*nodes.iter().find(|n| left_over(n)).unwrap()];
+
+        loop {
+            let current = *walk.last().unwrap();
+            let previous = *nodes
+                .iter()
+                .find(|p| left_over(p) && self.has_edge(**p, current))
+                .unwrap();
+
+            if let Some(start) = walk.iter().position(|n| *n == previous) {
+                let mut cycle: Vec<T> = walk[start..]
+                    .iter()
+                    .rev()
+                    .map(|n| self.names[*n].clone())
+                    .collect();
+                cycle.push(cycle[0].clone());
+                return Err(Cycle(cycle));
+            }
+            walk.push(previous);
+        }
+    }
+
+    /// The nodes reachable from every node by following one or more edges.
+    pub fn transitive_closure(&self) -> Vec<BitSet> {
+        self.nodes()
+            .map(|start| {
+                let mut reachable = BitSet::new(self.len());
+                let mut stack = self.adjacency[start].clone();
+                while let Some(n) = stack.pop() {
+                    if reachable.insert(n) {
+                        stack.extend(&self.adjacency[n]);
+                    }
+                }
+                reachable
+            })
+            .collect()
+    }
+
+    /// Groups of nodes connected by edges in either direction, each sorted by id.
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut reverse = vec![vec![]; self.len()];
+        for a in self.nodes() {
+            for &b in &self.adjacency[a] {
+                reverse[b].push(a);
+            }
+        }
+
+        let mut seen = BitSet::new(self.len());
+        let mut components = vec![];
+
+        for start in self.nodes() {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut component = vec![];
+            let mut stack = vec![start];
+            while let Some(n) = stack.pop() {
+                component.push(n);
+                for &m in self.adjacency[n].iter().chain(&reverse[n]) {
+                    if seen.insert(m) {
+                        stack.push(m);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// All triangles of an undirected graph, each sorted by id and reported once.
+    pub fn triangles(&self) -> Vec<[NodeId; 3]> {
+        let mut triangles = vec![];
+        for a in self.nodes() {
+            for &b in self.adjacency[a].iter().filter(|b| **b > a) {
+                let common = self.edges[a].intersection(&self.edges[b]);
+                triangles.extend(common.iter().filter(|c| *c > b).map(|c| [a, b, c]));
+            }
+        }
+        triangles
+    }
+
+    /// The nodes in degeneracy order: each node has the fewest neighbors among the nodes after it.
+    /// Every node then has at most the graph's degeneracy of later neighbors, which keeps the
+    /// clique searches started from it small.
+    pub fn degeneracy_order(&self) -> Vec<NodeId> {
+        let mut degree: Vec<usize> = self.adjacency.iter().map(Vec::len).collect();
+        let mut buckets = vec![vec![]; degree.iter().max().map_or(0, |d| d + 1)];
+        for v in self.nodes() {
+            buckets[degree[v]].push(v);
+        }
+
+        let mut removed = BitSet::new(self.len());
+        let mut order = Vec::with_capacity(self.len());
+        let mut low = 0;
+        while order.len() < self.len() {
+            let Some(v) = buckets[low].pop() else {
+                low += 1;
+                continue;
+            };
+            // buckets keep stale entries of nodes whose degree dropped since
+            if removed.contains(v) || degree[v] != low {
+                continue;
+            }
+
+            removed.insert(v);
+            order.push(v);
+            for &u in &self.adjacency[v] {
+                if !removed.contains(u) {
+                    degree[u] -= 1;
+                    buckets[degree[u]].push(u);
+                }
+            }
+            low = low.saturating_sub(1);
+        }
+        order
+    }
+
+    /// All maximal cliques of at least `min_len` nodes of an undirected graph, each sorted by id.
+    pub fn maximal_cliques(&self, min_len: usize) -> Vec<Vec<NodeId>> {
+        let mut cliques = vec![];
+        self.each_maximal_clique(min_len, &mut |clique| {
+            let mut clique = clique.to_vec();
+            clique.sort_unstable();
+            cliques.push(clique);
+            min_len
+        });
+        cliques
+    }
+
+    /// All cliques of exactly `len` nodes, each sorted by id and reported once.
+    /// They are the subsets of the maximal cliques, so `cliques(3)` are the [`triangles`](Self::triangles).
+    pub fn cliques(&self, len: usize) -> Vec<Vec<NodeId>> {
+        let mut cliques: Vec<Vec<NodeId>> = self
+            .maximal_cliques(len)
+            .iter()
+            .flat_map(|clique| clique.iter().copied().combinations(len))
+            .collect();
+        cliques.sort_unstable();
+        cliques.dedup();
+        cliques
+    }
+
+    /// A largest set of nodes that are all connected to each other, sorted by id.
+    pub fn max_clique(&self) -> Vec<NodeId> {
+        let mut best = vec![];
+        self.each_maximal_clique(1, &mut |clique| {
+            best = clique.to_vec();
+            clique.len() + 1
+        });
+        best.sort_unstable();
+        best
+    }
+
+    /// Calls `report` with every maximal clique of at least `min_len` nodes, which returns the
+    /// smallest length still of interest. Uses Bron–Kerbosch with pivoting on an undirected graph,
+    /// starting once from every node in [degeneracy order](Self::degeneracy_order) with the
+    /// later neighbors as candidates and the earlier ones excluded.
+    fn each_maximal_clique(&self, mut min_len: usize, report: &mut dyn FnMut(&[NodeId]) -> usize) {
+        let order = self.degeneracy_order();
+        let mut rank = vec![0; self.len()];
+        for (i, v) in order.iter().enumerate() {
+            rank[*v] = i;
+        }
+
+        for &v in &order {
+            let (later, earlier): (Vec<NodeId>, Vec<NodeId>) =
+                self.adjacency[v].iter().partition(|u| rank[**u] > rank[v]);
+            if later.len() + 1 < min_len {
+                continue;
+            }
+
+            self.bron_kerbosch(
+                &mut vec![v],
+                later.into_iter().collect(),
+                earlier.into_iter().collect(),
+                &mut min_len,
+                report,
+            );
+        }
+    }
+
+    fn bron_kerbosch(
+        &self,
+        clique: &mut Vec<NodeId>,
+        mut candidates: BitSet,
+        mut excluded: BitSet,
+        min_len: &mut usize,
+        report: &mut dyn FnMut(&[NodeId]) -> usize,
+    ) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() >= *min_len {
+                *min_len = report(clique);
+            }
+            return;
+        }
+        if clique.len() + candidates.len() < *min_len {
+            return;
+        }
+
+        // nodes adjacent to the pivot are part of a clique that contains the pivot or
+        // one of its other non-neighbors, so they need no branch of their own.
+        let pivot = candidates
+            .union(&excluded)
+            .iter()
+            .max_by_key(|u| candidates.intersection_len(&self.edges[*u]))
+            .unwrap();
+
+        for v in candidates.difference(&self.edges[pivot]).iter() {
+            clique.push(v);
+            self.bron_kerbosch(
+                clique,
+                candidates.intersection(&self.edges[v]),
+                excluded.intersection(&self.edges[v]),
+                min_len,
+                report,
+            );
+            clique.pop();
+            candidates.remove(v);
+            excluded.grow(v + 1);
+            excluded.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find_merges_sets() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.set_len(2), 4);
+        assert_eq!(sets.set_len(5), 1);
+        assert_eq!(sets.sets(), 3);
+    }
+
+    #[test]
+    fn bitset_operations() {
+        let a: BitSet = [1, 64, 130].into_iter().collect();
+        let b: BitSet = [1, 2, 130].into_iter().collect();
+        assert_eq!(a.len(), 3);
+        assert!(a.contains(64) && !a.contains(65) && !a.contains(1000));
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), [1, 130]);
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), [64]);
+        assert_eq!(a.intersection_len(&b), 2);
+    }
+
+    #[test]
+    fn interns_nodes() {
+        let mut graph = Graph::directed();
+        let (a, b) = graph.add_edge("a", "b");
+        graph.add_edge("a", "b");
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.id(&"b"), Some(b));
+        assert_eq!(*graph.name(a), "a");
+        assert_eq!(graph.neighbors(a), [b]);
+        assert!(graph.has_edge(a, b) && !graph.has_edge(b, a));
+    }
+
+    #[test]
+    fn toposort_orders_edges_forward() {
+        let mut graph = Graph::directed();
+        for (a, b) in [(3, 1), (1, 2), (3, 2), (4, 5)] {
+            graph.add_edge(a, b);
+        }
+        let sorted = graph.toposort().unwrap();
+        let position = |n| sorted.iter().position(|id| *graph.name(*id) == n).unwrap();
+        assert!(position(3) < position(1) && position(1) < position(2));
+        assert!(position(4) < position(5));
+
+        let subset = [graph.id(&2).unwrap(), graph.id(&3).unwrap()];
+        let sorted = graph.toposort_of(&subset).unwrap();
+        assert_eq!(
+            sorted.iter().map(|id| *graph.name(*id)).collect::<Vec<_>>(),
+            [3, 2]
+        );
+    }
+
+    #[test]
+    fn toposort_reports_cycle() {
+        let mut graph = Graph::directed();
+        for (a, b) in [("x", "a"), ("a", "b"), ("b", "c"), ("c", "a")] {
+            graph.add_edge(a, b);
+        }
+        let cycle = graph.toposort().unwrap_err();
+        assert_eq!(cycle.0.len(), 4);
+        assert_eq!(cycle.0.first(), cycle.0.last());
+        assert!(!cycle.0.contains(&"x"));
+        assert!(cycle.to_string().starts_with("cycle: "));
+    }
+
+    #[test]
+    fn closure_and_components() {
+        let mut graph = Graph::directed();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(3, 4);
+        let closure = graph.transitive_closure();
+        assert_eq!(closure[0].iter().collect::<Vec<_>>(), [1, 2]);
+        assert!(closure[2].is_empty());
+        assert_eq!(graph.components(), [vec![0, 1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn triangles_and_cliques() {
+        let mut graph = Graph::undirected();
+        // a 4-clique 0..=3, plus a triangle 3, 4, 5 and a pendant 6
+        for (a, b) in [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)] {
+            graph.add_edge(a, b);
+        }
+        for (a, b) in [(3, 4), (4, 5), (5, 3), (5, 6)] {
+            graph.add_edge(a, b);
+        }
+        assert_eq!(graph.triangles().len(), 4 + 1);
+        let clique: Vec<i32> = graph
+            .max_clique()
+            .iter()
+            .map(|id| *graph.name(*id))
+            .collect();
+        assert_eq!(clique, [0, 1, 2, 3]);
+
+        let names = |cliques: Vec<Vec<NodeId>>| -> Vec<Vec<i32>> {
+            let mut names: Vec<Vec<i32>> = cliques
+                .iter()
+                .map(|clique| clique.iter().map(|id| *graph.name(*id)).collect())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(graph.maximal_cliques(1)),
+            [vec![0, 1, 2, 3], vec![3, 4, 5], vec![5, 6]]
+        );
+        assert_eq!(
+            names(graph.maximal_cliques(3)),
+            [vec![0, 1, 2, 3], vec![3, 4, 5]]
+        );
+        assert_eq!(graph.cliques(3).len(), graph.triangles().len());
+        assert_eq!(graph.cliques(4).len(), 1);
+    }
+
+    #[test]
+    fn degeneracy_order_peels_low_degrees_first() {
+        let mut graph = Graph::undirected();
+        // a triangle with a path 2 - 3 - 4 hanging off it
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4)] {
+            graph.add_edge(a, b);
+        }
+        let order = graph.degeneracy_order();
+        let peeled: Vec<i32> = order.iter().map(|id| *graph.name(*id)).collect();
+        assert_eq!(peeled[..2], [4, 3]);
+        // every node has at most 2 neighbors after it, the degeneracy of a triangle
+        for (i, v) in order.iter().enumerate() {
+            let later = graph.neighbors(*v).iter();
+            assert!(later.filter(|u| order[i..].contains(u)).count() <= 2);
+        }
+    }
+}
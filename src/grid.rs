@@ -0,0 +1,460 @@
+//! A two dimensional grid with flat storage, as used by most map based puzzles.
+//!
+//! Positions are [`Point`]s where `x` is the column and `y` the row, both starting at the top left.
+//! Points outside of the grid are fine to compute with, [`Grid::get`] and the neighbor iterators
+//! do the bounds checking.
+//!
+//! ```ignore
+//! use advent_of_code::grid::{Direction, Grid};
+//!
+//! let grid = Grid::parse(input);
+//! let start = grid.find(&'S').unwrap();
+//! let open = grid.neighbors4(start).filter(|p| grid[*p] != '#').count();
+//! ```
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ZERO: Point = Point::new(0, 0);
+
+    /// Offsets of the 4 orthogonal neighbors, clockwise starting with up.
+    pub const ORTHOGONAL: [Point; 4] = [
+        Point::new(0, -1),
+        Point::new(1, 0),
+        Point::new(0, 1),
+        Point::new(-1, 0),
+    ];
+
+    /// Offsets of the 4 diagonal neighbors, clockwise starting with up-right.
+    pub const DIAGONAL: [Point; 4] = [
+        Point::new(1, -1),
+        Point::new(1, 1),
+        Point::new(-1, 1),
+        Point::new(-1, -1),
+    ];
+
+    /// Offsets of all 8 neighbors, clockwise starting with up.
+    pub const ADJACENT: [Point; 8] = [
+        Point::new(0, -1),
+        Point::new(1, -1),
+        Point::new(1, 0),
+        Point::new(1, 1),
+        Point::new(0, 1),
+        Point::new(-1, 1),
+        Point::new(-1, 0),
+        Point::new(-1, -1),
+    ];
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i32) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting with up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn offset(self) -> Point {
+        Point::ORTHOGONAL[self as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Parses an arrow (`^>v<`) into a direction.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        ['^', '>', 'v', '<'][self as usize]
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order.
+    ///
+    /// # Panics
+    /// When the number of cells is not `width * height`.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "expected {width}x{height} cells, got {}",
+            cells.len()
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, converting every character with `f`.
+    ///
+    /// # Panics
+    /// When the lines differ in length.
+    pub fn parse_with(input: &str, f: impl FnMut(char) -> T) -> Self {
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let width = lines.first().map_or(0, |l| l.chars().count());
+
+        for (row, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            assert_eq!(
+                len,
+                width,
+                "line {} has {len} cells, expected {width}",
+                row + 1
+            );
+        }
+
+        let cells = lines.iter().flat_map(|l| l.chars()).map(f).collect();
+        Self::from_vec(width, lines.len(), cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    /// Index of `p` into the flat storage, `None` when out of bounds.
+    pub fn index_of(&self, p: Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index_of(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Sets the cell at `p`, returning whether `p` was inside the grid.
+    pub fn set(&mut self, p: Point, value: T) -> bool {
+        match self.get_mut(p) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// All points in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The orthogonal neighbors of `p` inside the grid.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Point::ORTHOGONAL
+            .into_iter()
+            .map(move |d| p + d)
+            .filter(|n| self.contains(*n))
+    }
+
+    /// The orthogonal and diagonal neighbors of `p` inside the grid.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Point::ADJACENT
+            .into_iter()
+            .map(move |d| p + d)
+            .filter(|n| self.contains(*n))
+    }
+
+    /// Position of the first cell (in row-major order) matching `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| self.point_of(i))
+    }
+
+    /// Positions of all cells matching `predicate`.
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, c)| predicate(c))
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Renders one line per row, converting every cell with `f`.
+    pub fn render(&self, mut f: impl FnMut(Point, &T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for (p, cell) in self.iter() {
+            out.push(f(p, cell));
+            if p.x as usize == self.width - 1 {
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Position of the first cell equal to `value`.
+    pub fn find(&self, value: &T) -> Option<Point> {
+        self.position(|c| c == value)
+    }
+
+    /// Positions of all cells equal to `value`.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a {
+        self.positions(move |c| c == value)
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Self {
+        Self::parse_with(input, |c| c)
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid of single digits.
+    ///
+    /// # Panics
+    /// On characters that are not digits.
+    pub fn parse_digits(input: &str) -> Self {
+        Self::parse_with(input, |c| {
+            c.to_digit(10)
+                .unwrap_or_else(|| panic!("expected a digit, got {c:?}")) as u8
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.index_of(p) {
+            Some(i) => &self.cells[i],
+            None => panic!(
+                "{p:?} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        match self.index_of(p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "{p:?} is outside of the {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|_, c| *c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#.S\n..#\nE..\n";
+
+    #[test]
+    fn parse_and_render() {
+        let grid = Grid::parse(INPUT);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid[Point::new(2, 1)], '#');
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2 has 2 cells")]
+    fn parse_ragged() {
+        Grid::parse("...\n..\n");
+    }
+
+    #[test]
+    fn find_cells() {
+        let grid = Grid::parse(INPUT);
+        assert_eq!(grid.find(&'S'), Some(Point::new(2, 0)));
+        assert_eq!(grid.find(&'E'), Some(Point::new(0, 2)));
+        assert_eq!(grid.find(&'X'), None);
+        assert_eq!(grid.find_all(&'#').count(), 2);
+    }
+
+    #[test]
+    fn neighbors_are_bounds_checked() {
+        let grid = Grid::parse(INPUT);
+        assert_eq!(grid.neighbors4(Point::ZERO).count(), 2);
+        assert_eq!(grid.neighbors8(Point::ZERO).count(), 3);
+        assert_eq!(grid.neighbors4(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+    }
+
+    #[test]
+    fn directions() {
+        let up = Direction::Up;
+        assert_eq!(up.turn_right(), Direction::Right);
+        assert_eq!(up.turn_left(), Direction::Left);
+        assert_eq!(up.reverse(), Direction::Down);
+        assert_eq!(Point::ZERO.step(up), Point::new(0, -1));
+        assert!(Direction::ALL
+            .iter()
+            .all(|d| Direction::from_arrow(d.arrow()) == Some(*d)));
+    }
+
+    #[test]
+    fn digits_and_map() {
+        let grid = Grid::parse_digits("012\n345\n");
+        assert_eq!(grid[Point::new(1, 1)], 4);
+        let doubled = grid.map(|d| u32::from(*d) * 2);
+        assert_eq!(doubled.cells(), &[0, 2, 4, 6, 8, 10]);
+        assert_eq!(grid.point_of(4), Point::new(1, 1));
+    }
+}
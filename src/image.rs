@@ -0,0 +1,525 @@
+//! RGB images with PNG, PPM and PBM writers, for looking at grid simulations outside of the terminal.
+//!
+//! A simulation renders its state to an [`Image`] per step and writes them as numbered files
+//! ([`write_numbered`]) or tiles many of them into [`ContactSheets`] to scan thousands of steps at once:
+//!
+//! ```ignore
+//! use advent_of_code::export;
+//! use advent_of_code::image::{self, ContactSheets, Image};
+//!
+//! if let Some(dir) = export::dir() {
+//!     let mut sheets = ContactSheets::new(dir, 10, 10)?;
+//!     for step in 0..10_000 {
+//!         // ...
+//!         sheets.push(Image::from_grid(&grid, 1, |_, lit| if *lit { image::WHITE } else { image::BLACK }))?;
+//!     }
+//!     sheets.finish()?;
+//! }
+//! ```
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::grid::{Grid, Point};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GRAY: Rgb = [64, 64, 64];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    /// Binary PPM (`P6`), uncompressed RGB.
+    Ppm,
+    /// Binary PBM (`P4`), one bit per pixel.
+    Pbm,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Pbm => "pbm",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            "pbm" => Some(ImageFormat::Pbm),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// Draws every cell of `grid` as a `scale` x `scale` square colored by `f`.
+    pub fn from_grid<T>(grid: &Grid<T>, scale: usize, mut f: impl FnMut(Point, &T) -> Rgb) -> Self {
+        let scale = scale.max(1);
+        let mut image = Image::new(grid.width() * scale, grid.height() * scale, BLACK);
+        for (p, cell) in grid.iter() {
+            image.fill(
+                p.x as usize * scale,
+                p.y as usize * scale,
+                scale,
+                scale,
+                f(p, cell),
+            );
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Sets a pixel, pixels outside of the image are ignored.
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Fills a rectangle, clipped to the image.
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.pixels[y * self.width + x] = color;
+            }
+        }
+    }
+
+    /// Copies `image` with its top left corner at (`x`, `y`), clipped to this image.
+    pub fn paste(&mut self, image: &Image, x: usize, y: usize) {
+        for dy in 0..image.height.min(self.height.saturating_sub(y)) {
+            for dx in 0..image.width.min(self.width.saturating_sub(x)) {
+                self.pixels[(y + dy) * self.width + x + dx] = image.pixels[dy * image.width + dx];
+            }
+        }
+    }
+
+    /// Writes the image to `path` in the format given by its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|e| ImageFormat::from_extension(&e.to_string_lossy()))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is not a .png, .ppm or .pbm file", path.display()),
+                )
+            })?;
+
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out, format)?;
+        out.flush()
+    }
+
+    pub fn write(&self, out: impl Write, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Png => self.write_png(out),
+            ImageFormat::Ppm => self.write_ppm(out),
+            ImageFormat::Pbm => self.write_pbm(out),
+        }
+    }
+
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels.concat())
+    }
+
+    /// Writes pixels with a brightness of at least half as black ink on white, the rest as white.
+    pub fn write_pbm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P4\n{} {}\n", self.width, self.height)?;
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let mut bytes = vec![0u8; self.width.div_ceil(8)];
+            for (x, [r, g, b]) in row.iter().enumerate() {
+                if u32::from(*r) + u32::from(*g) + u32::from(*b) >= 3 * 128 {
+                    bytes[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            out.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_png(&self, mut out: impl Write) -> io::Result<()> {
+        let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "image is too large");
+        let width = u32::try_from(self.width).map_err(too_large)?;
+        let height = u32::try_from(self.height).map_err(too_large)?;
+
+        let mut header = vec![];
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        // 8 bit RGB, default compression and filtering, no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+
+        // every row starts with filter type 0 (none).
+        let stride = 3 * self.width + 1;
+        let mut raw = Vec::with_capacity(stride * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(row.concat());
+        }
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut out, b"IHDR", &header)?;
+        write_chunk(&mut out, b"IDAT", &zlib(&raw, &[3, stride]))?;
+        write_chunk(&mut out, b"IEND", &[])
+    }
+}
+
+/// Tiles `images` row by row into one image with `columns` columns, separated by a gray border.
+/// Tiles are as large as the largest image.
+pub fn contact_sheet(images: &[Image], columns: usize) -> Image {
+    const BORDER: usize = 2;
+
+    let columns = columns.clamp(1, images.len().max(1));
+    let rows = images.len().div_ceil(columns);
+    let tile_width = images.iter().map(Image::width).max().unwrap_or(0);
+    let tile_height = images.iter().map(Image::height).max().unwrap_or(0);
+
+    let mut sheet = Image::new(
+        columns * (tile_width + BORDER) + BORDER,
+        rows * (tile_height + BORDER) + BORDER,
+        GRAY,
+    );
+    for (i, image) in images.iter().enumerate() {
+        let x = BORDER + (i % columns) * (tile_width + BORDER);
+        let y = BORDER + (i / columns) * (tile_height + BORDER);
+        sheet.paste(image, x, y);
+    }
+    sheet
+}
+
+/// Writes `images` to `dir` as `frame_0001.<ext>`, `frame_0002.<ext>`, ... and returns their number.
+pub fn write_numbered(
+    dir: impl AsRef<Path>,
+    images: impl IntoIterator<Item = Image>,
+    format: ImageFormat,
+) -> io::Result<usize> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let mut count = 0;
+    for image in images {
+        count += 1;
+        image.save(dir.join(format!("frame_{count:04}.{}", format.extension())))?;
+    }
+    Ok(count)
+}
+
+/// Writes images as they come into PNG contact sheets `sheet_0001.png`, `sheet_0002.png`, ...
+/// of `columns` x `rows` tiles, so long simulations don't have to keep all images in memory.
+///
+/// Image `i` (counting from 0) is on sheet `i / (columns * rows) + 1`, tiled row by row.
+pub struct ContactSheets {
+    dir: PathBuf,
+    columns: usize,
+    per_sheet: usize,
+    tiles: Vec<Image>,
+    sheets: usize,
+}
+
+impl ContactSheets {
+    pub fn new(dir: impl Into<PathBuf>, columns: usize, rows: usize) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            columns: columns.max(1),
+            per_sheet: columns.max(1) * rows.max(1),
+            tiles: vec![],
+            sheets: 0,
+        })
+    }
+
+    pub fn push(&mut self, image: Image) -> io::Result<()> {
+        self.tiles.push(image);
+        if self.tiles.len() == self.per_sheet {
+            self.write_sheet()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the last, partially filled sheet and returns the number of sheets.
+    pub fn finish(mut self) -> io::Result<usize> {
+        if !self.tiles.is_empty() {
+            self.write_sheet()?;
+        }
+        Ok(self.sheets)
+    }
+
+    fn write_sheet(&mut self) -> io::Result<()> {
+        self.sheets += 1;
+        let path = self.dir.join(format!("sheet_{:04}.png", self.sheets));
+        contact_sheet(&self.tiles, self.columns).save(path)?;
+        self.tiles.clear();
+        Ok(())
+    }
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "image is too large"))?;
+    out.write_all(&len.to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    out.write_all(&crc32(&[kind, data]).to_be_bytes())
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = !0u32;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += u32::from(*byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Compresses `data` into a zlib stream with a single fixed Huffman deflate block.
+///
+/// Only repeats at the given `distances` are searched (the previous pixel and the previous row),
+/// which is enough for the large single colored areas of rendered grids.
+fn zlib(data: &[u8], distances: &[usize]) -> Vec<u8> {
+    const MAX_DISTANCE: usize = 32768;
+    const MIN_MATCH: usize = 3;
+    const MAX_MATCH: usize = 258;
+
+    let mut bits = BitWriter::default();
+    // final block, fixed Huffman codes.
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut i = 0;
+    while i < data.len() {
+        let best = distances
+            .iter()
+            .filter(|d| **d <= i && **d <= MAX_DISTANCE)
+            .map(|&d| {
+                let len = (0..MAX_MATCH.min(data.len() - i))
+                    .take_while(|k| data[i + k] == data[i + k - d])
+                    .count();
+                (len, d)
+            })
+            .max();
+
+        match best {
+            Some((len, distance)) if len >= MIN_MATCH => {
+                bits.length(len);
+                bits.distance(distance);
+                i += len;
+            }
+            _ => {
+                bits.symbol(u16::from(data[i]));
+                i += 1;
+            }
+        }
+    }
+    bits.symbol(256);
+
+    // deflate with a 32K window and no preset dictionary.
+    let mut out = vec![0x78, 0x01];
+    out.extend(bits.finish());
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Writes the bits of a deflate stream, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= u64::from(value) << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Huffman codes are stored starting with their most significant bit.
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    /// A literal/length symbol of the fixed Huffman code.
+    fn symbol(&mut self, symbol: u16) {
+        let symbol = u32::from(symbol);
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, len: usize) {
+        const BASES: [usize; 29] = [
+            3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99,
+            115, 131, 163, 195, 227, 258,
+        ];
+        const EXTRA: [u32; 29] = [
+            0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+        ];
+
+        let code = BASES.iter().rposition(|base| *base <= len).unwrap();
+        self.symbol(257 + code as u16);
+        self.write((len - BASES[code]) as u32, EXTRA[code]);
+    }
+
+    fn distance(&mut self, distance: usize) {
+        const BASES: [usize; 30] = [
+            1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025,
+            1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+        ];
+
+        let code = BASES.iter().rposition(|base| *base <= distance).unwrap();
+        let extra = (code.saturating_sub(2) / 2) as u32;
+        self.write_code(code as u32, 5);
+        self.write((distance - BASES[code]) as u32, extra);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        let grid = Grid::parse("#.#\n.#.\n");
+        Image::from_grid(&grid, 2, |_, c| if *c == '#' { WHITE } else { BLACK })
+    }
+
+    #[test]
+    fn draws_grids() {
+        let image = checkerboard();
+        assert_eq!((image.width(), image.height()), (6, 4));
+        assert_eq!(image.get(1, 1), Some(WHITE));
+        assert_eq!(image.get(2, 1), Some(BLACK));
+        assert_eq!(image.get(3, 3), Some(WHITE));
+        assert_eq!(image.get(6, 0), None);
+    }
+
+    #[test]
+    fn writes_ppm_and_pbm() {
+        let mut image = Image::new(9, 1, BLACK);
+        image.set(0, 0, WHITE);
+        image.set(8, 0, [200, 100, 150]);
+
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n9 1\n255\n"));
+        assert_eq!(ppm.len(), 11 + 9 * 3);
+        assert_eq!(ppm[ppm.len() - 3..], [200, 100, 150]);
+
+        let mut pbm = vec![];
+        image.write_pbm(&mut pbm).unwrap();
+        assert_eq!(pbm, b"P4\n9 1\n\x80\x80");
+    }
+
+    #[test]
+    fn writes_png_chunks() {
+        let mut png = vec![];
+        checkerboard().write_png(&mut png).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x06\0\0\0\x04\x08\x02"));
+        // the IEND chunk has a well known checksum.
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(&[b"123456789"]), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn compresses_runs() {
+        // a fixed Huffman block of "aaaaaa": literal 'a', then length 5 at distance 1.
+        let stream = zlib(b"aaaaaa", &[1]);
+        assert_eq!(
+            stream,
+            [0x78, 0x01, 0x4b, 0x04, 0x03, 0x00, 0x07, 0xfb, 0x02, 0x47]
+        );
+    }
+
+    #[test]
+    fn tiles_contact_sheets() {
+        let tiles = vec![Image::new(2, 1, WHITE); 3];
+        let sheet = contact_sheet(&tiles, 2);
+
+        assert_eq!(
+            (sheet.width(), sheet.height()),
+            (2 + 2 * (2 + 2), 2 + 2 * (1 + 2))
+        );
+        assert_eq!(sheet.get(2, 2), Some(WHITE));
+        assert_eq!(sheet.get(4, 2), Some(GRAY));
+        assert_eq!(sheet.get(6, 2), Some(WHITE));
+        assert_eq!(sheet.get(2, 5), Some(WHITE));
+        assert_eq!(sheet.get(6, 5), Some(GRAY));
+    }
+}
@@ -1,3 +1,13 @@
+pub mod export;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod log;
+pub mod parse;
+pub mod pathfinding;
+pub mod property;
 pub mod template;
+pub mod visualize;
+pub mod xorshift;
 
 // Use this file to add helper functions and additional modules.
@@ -0,0 +1,197 @@
+//! Diagnostics for solutions, kept apart from the answers on stdout.
+//!
+//! Messages and progress bars go to stderr. Warnings always show, `info` and `debug` messages and
+//! progress bars only with `--verbose` (`cargo solve <day> --verbose`). Everything is muted while
+//! timing with `--time`, which also skips formatting the messages.
+//!
+//! ```ignore
+//! use advent_of_code::log;
+//!
+//! log::debug!("{design} -> {count}");
+//! let mut progress = log::Progress::new("obstacles", candidates.len());
+//! for candidate in candidates {
+//!     // ...
+//!     progress.inc();
+//! }
+//! ```
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+use colored::Colorize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn label(self) -> colored::ColoredString {
+        match self {
+            Level::Warn => "warn ".yellow(),
+            Level::Info => "info ".green(),
+            Level::Debug => "debug".dimmed(),
+        }
+    }
+}
+
+fn level_for(args: &[String]) -> Option<Level> {
+    let has_flag = |flag: &str| args.iter().any(|x| x == flag);
+    if has_flag("--time") {
+        None
+    } else if has_flag("--verbose") {
+        Some(Level::Debug)
+    } else {
+        Some(Level::Warn)
+    }
+}
+
+/// The most detailed level that is printed, `None` while timing.
+pub fn max_level() -> Option<Level> {
+    static MAX_LEVEL: OnceLock<Option<Level>> = OnceLock::new();
+    *MAX_LEVEL.get_or_init(|| level_for(&env::args().collect::<Vec<_>>()))
+}
+
+/// Returns `true` if messages of `level` are printed.
+pub fn enabled(level: Level) -> bool {
+    max_level().is_some_and(|max| level <= max)
+}
+
+#[doc(hidden)]
+pub fn write(level: Level, args: fmt::Arguments) {
+    eprintln!("[{}] {args}", level.label());
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)*));
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_warn {
+    ($($arg:tt)*) => {
+        $crate::__log!($crate::log::Level::Warn, $($arg)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_info {
+    ($($arg:tt)*) => {
+        $crate::__log!($crate::log::Level::Info, $($arg)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_debug {
+    ($($arg:tt)*) => {
+        $crate::__log!($crate::log::Level::Debug, $($arg)*)
+    };
+}
+
+/// Prints a warning to stderr, unless timing.
+pub use crate::__log_warn as warn;
+
+/// Prints a message to stderr with `--verbose`.
+pub use crate::__log_info as info;
+
+/// Prints a detailed message to stderr with `--verbose`.
+pub use crate::__log_debug as debug;
+
+const BAR_WIDTH: usize = 30;
+
+fn percent(done: usize, total: usize) -> usize {
+    (done.min(total) * 100).checked_div(total).unwrap_or(100)
+}
+
+/// A progress bar on stderr, drawn with `--verbose` when stderr is a terminal.
+/// Redraws only when the percentage changes and clears its line when dropped.
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: usize,
+    shown: Option<usize>,
+    visible: bool,
+}
+
+impl Progress {
+    pub fn new(label: impl Into<String>, total: usize) -> Self {
+        Self {
+            label: label.into(),
+            total,
+            done: 0,
+            shown: None,
+            visible: enabled(Level::Info) && io::stderr().is_terminal(),
+        }
+    }
+
+    pub fn inc(&mut self) {
+        self.set(self.done + 1);
+    }
+
+    pub fn set(&mut self, done: usize) {
+        self.done = done;
+        if !self.visible {
+            return;
+        }
+
+        let percent = percent(done, self.total);
+        if self.shown == Some(percent) {
+            return;
+        }
+        self.shown = Some(percent);
+
+        let filled = percent * BAR_WIDTH / 100;
+        eprint!(
+            "\r{} [{}{}] {percent:>3}%",
+            self.label,
+            "#".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled)
+        );
+        // A failed flush only delays the bar.
+        let _ = io::stderr().flush();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if self.shown.is_some() {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn level_follows_flags() {
+        assert_eq!(level_for(&args(&["01"])), Some(Level::Warn));
+        assert_eq!(level_for(&args(&["01", "--verbose"])), Some(Level::Debug));
+        assert_eq!(level_for(&args(&["01", "--verbose", "--time"])), None);
+    }
+
+    #[test]
+    fn percent_is_clamped() {
+        assert_eq!(percent(0, 4), 0);
+        assert_eq!(percent(3, 4), 75);
+        assert_eq!(percent(5, 4), 100);
+        assert_eq!(percent(0, 0), 100);
+    }
+}
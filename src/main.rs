@@ -2,78 +2,148 @@ use advent_of_code::template::commands::{all, download, read, scaffold, solve, t
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{aoc_year, format_countdown, next_unlock, Day};
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::commands::solve::SolutionArgs;
+    use advent_of_code::template::DaySet;
+    use std::env;
     use std::process;
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            days: DaySet,
         },
         Read {
-            day: Day,
+            days: DaySet,
         },
         Scaffold {
-            day: Day,
+            days: DaySet,
             download: bool,
             overwrite: bool,
         },
         Solve {
-            day: Day,
+            days: DaySet,
             release: bool,
             dhat: bool,
-            submit: Option<u8>,
+            solution_args: SolutionArgs,
         },
         All {
+            days: Option<DaySet>,
             release: bool,
+            only_part: Option<u8>,
+            skip_slow: Option<f64>,
         },
         Time {
             all: bool,
-            day: Option<Day>,
+            days: Option<DaySet>,
             store: bool,
+            check: bool,
+            only_part: Option<u8>,
+            skip_slow: Option<f64>,
         },
         #[cfg(feature = "today")]
         Today,
     }
 
+    /// Stored timing (in milliseconds) above which `--skip-slow` skips a day.
+    const DEFAULT_SLOW_THRESHOLD_MILLIS: f64 = 1000.0;
+
+    fn parse_only_part(args: &mut pico_args::Arguments) -> Result<Option<u8>, pico_args::Error> {
+        let only_part = args.opt_value_from_str("--only-part")?;
+        if only_part.is_some_and(|part| part != 1 && part != 2) {
+            eprintln!("`--only-part` expects either 1 or 2.");
+            process::exit(1);
+        }
+        Ok(only_part)
+    }
+
+    fn parse_skip_slow(args: &mut pico_args::Arguments) -> Result<Option<f64>, pico_args::Error> {
+        let skip_slow = args.contains("--skip-slow");
+        let threshold = args.opt_value_from_str("--slow-threshold")?;
+        Ok(skip_slow.then(|| threshold.unwrap_or(DEFAULT_SLOW_THRESHOLD_MILLIS)))
+    }
+
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        let mut args: Vec<_> = env::args_os().skip(1).collect();
+        // everything after `--` is passed on to the solution unchanged.
+        let mut trailing = match args.iter().position(|arg| arg == "--") {
+            Some(i) => {
+                let trailing = args.split_off(i + 1);
+                args.pop();
+                trailing
+                    .into_iter()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect()
+            }
+            None => vec![],
+        };
+        let mut args = pico_args::Arguments::from_vec(args);
 
         let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-            },
+            Some("all") => {
+                let release = args.contains("--release");
+                let only_part = parse_only_part(&mut args)?;
+                let skip_slow = parse_skip_slow(&mut args)?;
+
+                AppArguments::All {
+                    days: args.opt_free_from_str()?,
+                    release,
+                    only_part,
+                    skip_slow,
+                }
+            }
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let check = args.contains("--check");
+                let only_part = parse_only_part(&mut args)?;
+                let skip_slow = parse_skip_slow(&mut args)?;
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    days: args.opt_free_from_str()?,
                     store,
+                    check,
+                    only_part,
+                    skip_slow,
                 }
             }
             Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+                days: args.free_from_str()?,
             },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+                days: args.free_from_str()?,
             },
             Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
+                days: args.free_from_str()?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
+            Some("solve") => {
+                let days: DaySet = args.free_from_str()?;
+                let submit = args.opt_value_from_str("--submit")?;
+
+                if submit.is_some() && days.len() > 1 {
+                    eprintln!("`--submit` can only be used when solving a single day.");
+                    process::exit(1);
+                }
+
+                AppArguments::Solve {
+                    days,
+                    release: args.contains("--release"),
+                    dhat: args.contains("--dhat"),
+                    solution_args: SolutionArgs {
+                        submit_part: submit,
+                        visualize: args.contains("--visualize"),
+                        export: args.opt_value_from_str("--export")?,
+                        verbose: args.contains("--verbose"),
+                        trailing: std::mem::take(&mut trailing),
+                    },
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -86,7 +156,8 @@ mod args {
             }
         };
 
-        let remaining = args.finish();
+        let mut remaining = args.finish();
+        remaining.extend(trailing.into_iter().map(Into::into));
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
@@ -102,26 +173,50 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::All {
+                days,
+                release,
+                only_part,
+                skip_slow,
+            } => all::handle(days, release, only_part, skip_slow),
+            AppArguments::Time {
+                days,
+                all,
+                store,
+                check,
+                only_part,
+                skip_slow,
+            } => {
+                if check {
+                    time::check();
+                } else {
+                    time::handle(days, all, store, only_part, skip_slow);
+                }
+            }
+            AppArguments::Download { days } => days.iter().for_each(download::handle),
+            AppArguments::Read { days } => days.iter().for_each(read::handle),
             AppArguments::Scaffold {
-                day,
+                days,
                 download,
                 overwrite,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
+                for day in days.iter() {
+                    scaffold::handle(day, overwrite);
+                    if download {
+                        download::handle(day);
+                    }
                 }
             }
             AppArguments::Solve {
-                day,
+                days,
                 release,
                 dhat,
-                submit,
-            } => solve::handle(day, release, dhat, submit),
+                solution_args,
+            } => {
+                for day in days.iter() {
+                    solve::handle(day, release, dhat, &solution_args);
+                }
+            }
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
@@ -132,9 +227,12 @@ fn main() {
                     }
                     None => {
                         eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
+                            "`today` command can only be run during the event in december. \
+                            Please use `scaffold` with a specific day."
                         );
+                        if let Some((day, left)) = aoc_year().and_then(next_unlock) {
+                            eprintln!("Day {day} unlocks in {}.", format_countdown(left));
+                        }
                         process::exit(1)
                     }
                 };
@@ -0,0 +1,418 @@
+//! Parsing of puzzle inputs with errors pointing at the offending line and column.
+//!
+//! The free functions cover the common formats, e.g. [`integers`] for "all numbers in a line"
+//! and [`sections`] for blank line separated blocks. [`Parser`] is a small cursor with
+//! combinators for everything else, a `{}` in a [`Parser::pattern`] matches an integer:
+//!
+//! ```ignore
+//! use advent_of_code::parse::Parser;
+//!
+//! let robots = Parser::new(input).lines(|p| p.pattern::<i32>("p={},{} v={},{}"))?;
+//! ```
+//!
+//! Sub-parsers created by [`Parser::lines`] and [`Parser::sections`] report positions
+//! relative to the whole input.
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// A parse failure at a 1-based line and column of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// All integers in `s`, a `-` directly in front of a number makes it negative unless it follows
+/// another number, as in the range `1-5`.
+pub fn integers<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    let mut parser = Parser::new(s);
+    let mut values = vec![];
+    while parser.skip_to_integer() {
+        values.push(parser.integer()?);
+    }
+    Ok(values)
+}
+
+/// The blocks of `input` separated by blank lines, blank lines at the start and end are ignored.
+pub fn sections(input: &str) -> Vec<&str> {
+    let mut sections = vec![];
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(s) = start.take() {
+                sections.push(&input[s..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        sections.push(&input[s..end]);
+    }
+
+    sections
+}
+
+/// Splits every non-empty line of `input` at the first `separator`, trimming keys and values.
+pub fn key_values<'a>(
+    input: &'a str,
+    separator: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+    Parser::new(input).lines(|p| {
+        let key = p.until(separator);
+        p.literal(separator)?;
+        Ok((key.trim(), p.take_rest().trim()))
+    })
+}
+
+/// A cursor over (a part of) the puzzle input.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            end: input.len(),
+        }
+    }
+
+    /// A parser for `start..end` of the same input.
+    fn sub(&self, start: usize, end: usize) -> Self {
+        Self {
+            input: self.input,
+            pos: start,
+            end,
+        }
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        let before = &self.input[..pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// The unparsed input.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..self.end]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.end
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Consumes the next character.
+    pub fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Consumes `expected` or fails without consuming anything.
+    pub fn literal(&mut self, expected: &str) -> Result<(), ParseError> {
+        if self.rest().starts_with(expected) {
+            self.pos += expected.len();
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "expected {expected:?}, found {:?}",
+                self.rest().lines().next().unwrap_or_default()
+            )))
+        }
+    }
+
+    /// Consumes characters while `predicate` holds, returning them.
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consumes the rest of the input.
+    pub fn take_rest(&mut self) -> &'a str {
+        let rest = self.rest();
+        self.pos = self.end;
+        rest
+    }
+
+    /// Consumes everything up to (not including) `delimiter` or the end of the line.
+    pub fn until(&mut self, delimiter: &str) -> &'a str {
+        let rest = self.rest();
+        let line = rest.find('\n').unwrap_or(rest.len());
+        let len = rest[..line].find(delimiter).unwrap_or(line);
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Skips spaces and tabs, but not newlines.
+    pub fn spaces(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// Consumes a non-empty run of letters, digits and underscores.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let word = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if word.is_empty() {
+            return Err(self.error("expected a word"));
+        }
+        Ok(word)
+    }
+
+    /// Consumes an integer with an optional leading `-`.
+    pub fn integer<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let negative = self.rest().starts_with('-');
+        if negative {
+            self.pos += 1;
+        }
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            self.pos = start;
+            return Err(self.error("expected an integer"));
+        }
+
+        let token = &self.input[start..self.pos];
+        token.parse().map_err(|_| {
+            self.error_at(
+                start,
+                format!("{token} is not a valid {}", std::any::type_name::<T>()),
+            )
+        })
+    }
+
+    /// Skips to the start of the next integer, returning false when there is none.
+    /// A `-` right after a digit separates a range like `1-5` and is not taken as a sign.
+    pub fn skip_to_integer(&mut self) -> bool {
+        let bytes = self.input.as_bytes();
+        match bytes[self.pos..self.end]
+            .iter()
+            .position(u8::is_ascii_digit)
+        {
+            Some(i) => {
+                let digit = self.pos + i;
+                let negative = digit > 0
+                    && bytes[digit - 1] == b'-'
+                    && (digit < 2 || !bytes[digit - 2].is_ascii_digit());
+                self.pos = if negative { digit - 1 } else { digit };
+                true
+            }
+            None => {
+                self.pos = self.end;
+                false
+            }
+        }
+    }
+
+    /// Matches `pattern` where every `{}` is an integer, returning the integers.
+    /// Whitespace in the pattern has to match exactly.
+    pub fn pattern<T: FromStr>(&mut self, pattern: &str) -> Result<Vec<T>, ParseError> {
+        let mut values = vec![];
+        let mut parts = pattern.split("{}");
+
+        self.literal(parts.next().unwrap_or_default())?;
+        for literal in parts {
+            values.push(self.integer()?);
+            self.literal(literal)?;
+        }
+
+        Ok(values)
+    }
+
+    /// Parses items separated by `separator` until the end of the line.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while self.literal(separator).is_ok() {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Runs `f`, restoring the position when it fails.
+    pub fn attempt<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Option<T> {
+        let start = self.pos;
+        let result = f(self).ok();
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    /// Fails unless only whitespace is left.
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.take_while(char::is_whitespace);
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!(
+                "unexpected {:?}",
+                self.rest().lines().next().unwrap_or_default()
+            )))
+        }
+    }
+
+    /// Parses every non-empty remaining line with `item`, which has to consume the whole line.
+    pub fn lines<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![];
+
+        while !self.is_empty() {
+            let rest = self.rest();
+            let len = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            let line = self.sub(self.pos, self.pos + rest[..len].trim_end().len());
+            self.pos += len;
+
+            if line.rest().trim().is_empty() {
+                continue;
+            }
+            let mut line = line;
+            items.push(item(&mut line)?);
+            line.end()?;
+        }
+
+        Ok(items)
+    }
+
+    /// Parses every remaining blank line separated section with `item`, which has to consume the whole section.
+    pub fn sections<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let rest = self.rest();
+        let offset = self.pos;
+        let mut items = vec![];
+
+        for section in sections(rest) {
+            let start = offset + (section.as_ptr() as usize - rest.as_ptr() as usize);
+            let mut parser = self.sub(start, start + section.len());
+            items.push(item(&mut parser)?);
+            parser.end()?;
+        }
+
+        self.pos = self.end;
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_integers() {
+        assert_eq!(integers::<i32>("p=0,4 v=3,-3"), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(integers::<u64>("a-b 12x34"), Ok(vec![12, 34]));
+        assert_eq!(integers::<u32>("1-5"), Ok(vec![1, 5]));
+        assert_eq!(integers::<i32>("3-7,-2--1"), Ok(vec![3, 7, -2, -1]));
+        assert_eq!(integers::<u8>("none"), Ok(vec![]));
+
+        let error = integers::<u8>("1, 300").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 4));
+    }
+
+    #[test]
+    fn split_sections() {
+        let input = "\na\nb\n\n\nc\r\n\n";
+        assert_eq!(sections(input), ["a\nb", "c"]);
+    }
+
+    #[test]
+    fn key_value_lines() {
+        let input = "x00: 1\ny01 : 0\n";
+        assert_eq!(key_values(input, ":"), Ok(vec![("x00", "1"), ("y01", "0")]));
+        let error = key_values("a: 1\nb 2\n", ":").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn patterns() {
+        let robots = Parser::new("p=0,4 v=3,-3\np=6,3 v=-1,-3\n")
+            .lines(|p| p.pattern::<i32>("p={},{} v={},{}"))
+            .unwrap();
+        assert_eq!(robots, [[0, 4, 3, -3], [6, 3, -1, -3]]);
+
+        let error = Parser::new("p=0,4 v=3,-3\np=6;3 v=-1,-3\n")
+            .lines(|p| p.pattern::<i32>("p={},{} v={},{}"))
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn errors_point_into_sections() {
+        let input = "Register A: 729\n\nProgram: 0,1,x\n";
+        let error = Parser::new(input)
+            .sections(|p| {
+                p.until(": ");
+                p.literal(": ")?;
+                p.separated(",", Parser::integer::<u32>)
+            })
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 14));
+        assert_eq!(error.to_string(), "line 3, column 14: expected an integer");
+    }
+
+    #[test]
+    fn lines_have_to_be_consumed() {
+        let error = Parser::new("1-2\n3-4x\n")
+            .lines(|p| p.pattern::<u32>("{}-{}"))
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn attempt_backtracks() {
+        let mut parser = Parser::new("mul(2,x)mul(3,4)");
+        let mut products = vec![];
+        while !parser.is_empty() {
+            match parser.attempt(|p| p.pattern::<u32>("mul({},{})")) {
+                Some(v) => products.push(v[0] * v[1]),
+                None => {
+                    parser.next_char();
+                }
+            }
+        }
+        assert_eq!(products, [12]);
+    }
+}
@@ -0,0 +1,420 @@
+//! Shortest path searches over any state type.
+//!
+//! A search is described by a start state and a successor function. [`bfs`] expects the
+//! successors as plain states (every step costs 1), [`dijkstra`] and [`astar`] as
+//! `(state, cost)` pairs.
+//!
+//! ```ignore
+//! use advent_of_code::pathfinding;
+//!
+//! let (steps, path) = pathfinding::bfs(
+//!     start,
+//!     |p| grid.neighbors4(*p).filter(|n| grid[*n] != '#').collect::<Vec<_>>(),
+//!     |p| *p == end,
+//! )?;
+//! ```
+//!
+//! [`dijkstra_all`] keeps every predecessor on a shortest path, [`ShortestPaths::states`]
+//! then yields all states that lie on any of the shortest paths and [`ShortestPaths::paths`]
+//! the paths themselves.
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Costs of the weighted searches, `Default` is expected to be zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// Marks the start state in the parent list.
+const NO_PARENT: usize = usize::MAX;
+
+/// States seen by a search, numbered in the order they were discovered.
+struct Discovered<S, C> {
+    states: Vec<S>,
+    parents: Vec<usize>,
+    costs: Vec<C>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Discovered<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Self {
+            states: vec![start.clone()],
+            parents: vec![NO_PARENT],
+            costs: vec![cost],
+            index: HashMap::from([(start, 0)]),
+        }
+    }
+
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![];
+        while i != NO_PARENT {
+            path.push(self.states[i].clone());
+            i = self.parents[i];
+        }
+        path.reverse();
+        path
+    }
+
+    fn into_costs(self) -> HashMap<S, C> {
+        self.states.into_iter().zip(self.costs).collect()
+    }
+}
+
+/// Breadth first search from `start` to the first state matching `is_goal`.
+/// Returns the number of steps and the path, including `start` and the goal.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut seen = Discovered::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&seen.states[i]) {
+            return Some((seen.costs[i], seen.path_to(i)));
+        }
+
+        let current = seen.states[i].clone();
+        for next in successors(&current) {
+            if let Entry::Vacant(entry) = seen.index.entry(next.clone()) {
+                entry.insert(seen.states.len());
+                queue.push_back(seen.states.len());
+                seen.states.push(next);
+                seen.parents.push(i);
+                seen.costs.push(seen.costs[i] + 1);
+            }
+        }
+    }
+
+    None
+}
+
+/// Number of steps from `start` to every reachable state.
+pub fn bfs_distances<S, I>(start: S, mut successors: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        let distance = distances[&current];
+        for next in successors(&current) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Cheapest path from `start` to the first state matching `is_goal`.
+/// Returns the total cost and the path, including `start` and the goal.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// Cost of the cheapest path from `start` to every reachable state.
+pub fn dijkstra_distances<S, C, I>(start: S, mut successors: impl FnMut(&S) -> I) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut seen = Discovered::new(start, C::default());
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        if cost > seen.costs[i] {
+            continue;
+        }
+
+        let current = seen.states[i].clone();
+        for (next, step) in successors(&current) {
+            if let Some(j) = relax(&mut seen, i, next, cost + step) {
+                heap.push(Reverse((cost + step, j)));
+            }
+        }
+    }
+
+    seen.into_costs()
+}
+
+/// A* search, `heuristic` must never overestimate the remaining cost to a goal.
+/// Returns the total cost and the path, including `start` and the goal.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let estimate = heuristic(&start);
+    let mut seen = Discovered::new(start, C::default());
+    let mut heap = BinaryHeap::from([Reverse((estimate, C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > seen.costs[i] {
+            continue;
+        }
+        if is_goal(&seen.states[i]) {
+            return Some((cost, seen.path_to(i)));
+        }
+
+        let current = seen.states[i].clone();
+        for (next, step) in successors(&current) {
+            if let Some(j) = relax(&mut seen, i, next, cost + step) {
+                let estimate = cost + step + heuristic(&seen.states[j]);
+                heap.push(Reverse((estimate, cost + step, j)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Records `next` as reached from `i` with `cost`, returning its index when that is an improvement.
+fn relax<S, C>(seen: &mut Discovered<S, C>, i: usize, next: S, cost: C) -> Option<usize>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+{
+    match seen.index.entry(next) {
+        Entry::Vacant(entry) => {
+            let j = seen.states.len();
+            seen.states.push(entry.key().clone());
+            entry.insert(j);
+            seen.parents.push(i);
+            seen.costs.push(cost);
+            Some(j)
+        }
+        Entry::Occupied(entry) => {
+            let j = *entry.get();
+            (cost < seen.costs[j]).then(|| {
+                seen.parents[j] = i;
+                seen.costs[j] = cost;
+                j
+            })
+        }
+    }
+}
+
+/// Every shortest path from a start state to the cheapest goals, see [`dijkstra_all`].
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S, C> {
+    /// Cost of the shortest paths.
+    pub cost: C,
+    /// Goal states reached with `cost`.
+    pub goals: Vec<S>,
+    /// All predecessors of a state on one of its cheapest paths.
+    pub predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash, C> ShortestPaths<S, C> {
+    /// All states that lie on any shortest path, walking the predecessors back from the goals.
+    pub fn states(&self) -> HashSet<S> {
+        let mut states: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+
+        while let Some(state) = stack.pop() {
+            for previous in self.predecessors.get(&state).into_iter().flatten() {
+                if states.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+
+        states
+    }
+
+    /// Every shortest path from the start to one of the goals, enumerated lazily since there can
+    /// be exponentially many. Each path starts with the start state and ends with its goal.
+    pub fn paths(&self) -> impl Iterator<Item = Vec<S>> + '_ {
+        let mut goals = self.goals.iter();
+        // the path walked back from a goal so far, with the index of the next predecessor to try
+        let mut stack: Vec<(S, usize)> = vec![];
+
+        std::iter::from_fn(move || loop {
+            let Some((state, next)) = stack.last_mut() else {
+                stack.push((goals.next()?.clone(), 0));
+                continue;
+            };
+            let predecessors = self.predecessors.get(state).map_or(&[][..], Vec::as_slice);
+
+            if predecessors.is_empty() && *next == 0 {
+                // reached the start
+                *next = 1;
+                return Some(stack.iter().rev().map(|(s, _)| s.clone()).collect());
+            }
+            match predecessors.get(*next) {
+                Some(previous) => {
+                    *next += 1;
+                    stack.push((previous.clone(), 0));
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        })
+    }
+}
+
+/// Like [`dijkstra`], but keeps all predecessors on equally cheap paths and every goal
+/// reached with the minimal cost.
+pub fn dijkstra_all<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<ShortestPaths<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut predecessors: HashMap<S, Vec<S>> = HashMap::new();
+    let mut states = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut best: Option<(C, Vec<S>)> = None;
+
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        let current = states[i].clone();
+        if cost > costs[&current] {
+            continue;
+        }
+        if let Some((best_cost, goals)) = &mut best {
+            if cost > *best_cost {
+                break;
+            }
+            if is_goal(&current) {
+                goals.push(current);
+                continue;
+            }
+        } else if is_goal(&current) {
+            best = Some((cost, vec![current]));
+            continue;
+        }
+
+        for (next, step) in successors(&current) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(known) if next_cost > *known => {}
+                Some(known) if next_cost == *known => {
+                    predecessors.entry(next).or_default().push(current.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![current.clone()]);
+                    heap.push(Reverse((next_cost, states.len())));
+                    states.push(next);
+                }
+            }
+        }
+    }
+
+    best.map(|(cost, goals)| ShortestPaths {
+        cost,
+        goals,
+        predecessors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 open grid with a wall in column 2 except for the bottom row.
+    fn neighbors(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y))
+            .filter(|&(x, y)| x != 2 || y == 4)
+            .collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let (steps, path) = bfs((0, 0), neighbors, |p| *p == (4, 0)).unwrap();
+        assert_eq!(steps, 12);
+        assert_eq!(path.len(), 13);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 0)));
+        assert!(bfs((0, 0), neighbors, |p| *p == (2, 0)).is_none());
+    }
+
+    #[test]
+    fn bfs_distance_map() {
+        let distances = bfs_distances((0, 0), neighbors);
+        assert_eq!(distances.len(), 21);
+        assert_eq!(distances[&(4, 0)], 12);
+    }
+
+    #[test]
+    fn weighted_searches_agree() {
+        // moving down is expensive
+        let weighted = |p: &(i32, i32)| {
+            neighbors(p)
+                .into_iter()
+                .map(|n| (n, if n.1 > p.1 { 3u32 } else { 1 }))
+                .collect::<Vec<_>>()
+        };
+        let goal = |p: &(i32, i32)| *p == (4, 0);
+
+        let (cost, _) = dijkstra((0, 0), weighted, goal).unwrap();
+        assert_eq!(cost, 4 * 3 + 4 + 4);
+
+        let manhattan = |p: &(i32, i32)| p.0.abs_diff(4) + p.1.unsigned_abs();
+        let (astar_cost, path) = astar((0, 0), weighted, manhattan, goal).unwrap();
+        assert_eq!(astar_cost, cost);
+        assert_eq!(path.last(), Some(&(4, 0)));
+
+        assert_eq!(dijkstra_distances((0, 0), weighted)[&(4, 0)], cost);
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        // every monotone path through a 3x3 grid is a shortest path to (2, 2)
+        let successors = |&(x, y): &(i32, i32)| {
+            [((x + 1, y), 1u32), ((x, y + 1), 1)]
+                .into_iter()
+                .filter(|((x, y), _)| *x < 3 && *y < 3)
+                .collect::<Vec<_>>()
+        };
+        let paths = dijkstra_all((0, 0), successors, |p| *p == (2, 2)).unwrap();
+        assert_eq!(paths.cost, 4);
+        assert_eq!(paths.goals, vec![(2, 2)]);
+        assert_eq!(paths.states().len(), 9);
+        assert_eq!(paths.predecessors[&(1, 1)].len(), 2);
+
+        let all: Vec<_> = paths.paths().collect();
+        assert_eq!(all.len(), 6);
+        assert!(all.iter().all(|path| path.len() == 5
+            && path.first() == Some(&(0, 0))
+            && path.last() == Some(&(2, 2))));
+    }
+}
@@ -0,0 +1,332 @@
+//! Property and differential testing for solutions.
+//!
+//! A [`Strategy`] generates random values (e.g. the stones of day 11 or the machines of day 13)
+//! and knows how to shrink them into simpler ones. [`check`] runs a property against many
+//! generated values and, on failure, shrinks the input to a minimal counterexample before panicking.
+//! [`check_equivalent`] is the differential variant comparing a naive and an optimized implementation.
+//!
+//! ```ignore
+//! use advent_of_code::property::{self, vec_of};
+//!
+//! property::check_equivalent(
+//!     &(vec_of(0..=1000u64, 1..=8), 0..=10usize),
+//!     |(stones, blinks)| naive(stones, *blinks),
+//!     |(stones, blinks)| fast(stones, *blinks),
+//! );
+//! ```
+//!
+//! Runs are deterministic. Set `AOC_PROPERTY_SEED` to use another seed and
+//! `AOC_PROPERTY_CASES` to change the number of generated cases (default 100).
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+const DEFAULT_SEED: u64 = 0x2024_1225;
+const DEFAULT_CASES: usize = 100;
+
+/// Upper bound on property evaluations while shrinking a counterexample.
+const MAX_SHRINK_STEPS: usize = 10_000;
+
+/// A small, seedable pseudo random number generator (xorshift64*).
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // NOTE: xorshift gets stuck on a zero state.
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Returns a value in the inclusive range `lo..=hi`.
+    pub fn between(&mut self, lo: u64, hi: u64) -> u64 {
+        match (hi - lo).checked_add(1) {
+            Some(span) => lo + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+/// Generates random values of a type and proposes simpler variants of a value.
+pub trait Strategy {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// Candidates that are "simpler" than `value`, simplest first.
+    /// Shrinking stops once no candidate fails the property anymore.
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        vec![]
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+macro_rules! impl_integer_strategy {
+    ($($t:ty),*) => {$(
+        /// Integers in the range, shrinking towards the lower bound.
+        impl Strategy for RangeInclusive<$t> {
+            type Value = $t;
+
+            fn generate(&self, rng: &mut Rng) -> $t {
+                let offset = rng.between(0, self.end().abs_diff(*self.start()) as u64);
+                self.start().wrapping_add(offset as $t)
+            }
+
+            fn shrink(&self, value: &$t) -> Vec<$t> {
+                let lo = *self.start();
+                if *value == lo {
+                    return vec![];
+                }
+                let half = lo.wrapping_add((value.abs_diff(lo) / 2) as $t);
+                let mut candidates = vec![lo, half, value - 1];
+                candidates.dedup();
+                candidates
+            }
+        }
+    )*};
+}
+
+impl_integer_strategy!(u8, u16, u32, u64, usize, i32, i64);
+
+/// Vectors with a length in `len`, see [`vec_of`].
+#[derive(Debug, Clone)]
+pub struct VecOf<S> {
+    element: S,
+    len: RangeInclusive<usize>,
+}
+
+/// Vectors of `element` values with a length in `len`.
+/// Shrinks by removing chunks and single elements, then by shrinking the elements.
+pub fn vec_of<S: Strategy>(element: S, len: RangeInclusive<usize>) -> VecOf<S> {
+    VecOf { element, len }
+}
+
+impl<S: Strategy> Strategy for VecOf<S> {
+    type Value = Vec<S::Value>;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value {
+        let len = self.len.generate(rng);
+        (0..len).map(|_| self.element.generate(rng)).collect()
+    }
+
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+        let mut candidates = vec![];
+        let min_len = *self.len.start();
+
+        let mut chunk = value.len() / 2;
+        while chunk > 0 {
+            if value.len() - chunk >= min_len {
+                for start in (0..=value.len() - chunk).step_by(chunk) {
+                    let mut shorter = value.clone();
+                    shorter.drain(start..start + chunk);
+                    candidates.push(shorter);
+                }
+            }
+            chunk /= 2;
+        }
+
+        for (i, element) in value.iter().enumerate() {
+            for simpler in self.element.shrink(element) {
+                let mut next = value.clone();
+                next[i] = simpler;
+                candidates.push(next);
+            }
+        }
+
+        candidates
+    }
+}
+
+/// A value that is always the same, e.g. to fix a parameter in a tuple strategy.
+#[derive(Debug, Clone)]
+pub struct Just<T>(pub T);
+
+impl<T: Clone + Debug> Strategy for Just<T> {
+    type Value = T;
+
+    fn generate(&self, _rng: &mut Rng) -> T {
+        self.0.clone()
+    }
+}
+
+macro_rules! impl_tuple_strategy {
+    ($($s:ident $i:tt),*) => {
+        impl<$($s: Strategy),*> Strategy for ($($s,)*) {
+            type Value = ($($s::Value,)*);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$i.generate(rng),)*)
+            }
+
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = vec![];
+                $(
+                    for simpler in self.$i.shrink(&value.$i) {
+                        let mut next = value.clone();
+                        next.$i = simpler;
+                        candidates.push(next);
+                    }
+                )*
+                candidates
+            }
+        }
+    };
+}
+
+impl_tuple_strategy!(A 0, B 1);
+impl_tuple_strategy!(A 0, B 1, C 2);
+impl_tuple_strategy!(A 0, B 1, C 2, D 3);
+
+/* -------------------------------------------------------------------------- */
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Repeatedly replaces `value` with the first simpler candidate that still fails.
+fn shrink<S: Strategy>(
+    strategy: &S,
+    mut value: S::Value,
+    fails: &impl Fn(&S::Value) -> bool,
+) -> (S::Value, usize) {
+    let mut steps = 0;
+
+    'outer: while steps < MAX_SHRINK_STEPS {
+        for candidate in strategy.shrink(&value) {
+            steps += 1;
+            if fails(&candidate) {
+                value = candidate;
+                continue 'outer;
+            }
+            if steps >= MAX_SHRINK_STEPS {
+                break 'outer;
+            }
+        }
+        break;
+    }
+
+    (value, steps)
+}
+
+/// Checks that `property` holds for generated values, panicking with a shrunk counterexample otherwise.
+/// A property that panics counts as failed.
+#[track_caller]
+pub fn check<S: Strategy>(strategy: &S, property: impl Fn(&S::Value) -> bool) {
+    check_explained(strategy, property, |_| None);
+}
+
+/// Like [`check`], adding `explain`'s description of the shrunk input to the panic message.
+#[track_caller]
+fn check_explained<S: Strategy>(
+    strategy: &S,
+    property: impl Fn(&S::Value) -> bool,
+    explain: impl Fn(&S::Value) -> Option<String>,
+) {
+    let seed = env_or("AOC_PROPERTY_SEED", DEFAULT_SEED);
+    let cases = env_or("AOC_PROPERTY_CASES", DEFAULT_CASES);
+
+    let fails = |value: &S::Value| {
+        !matches!(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| property(value))),
+            Ok(true)
+        )
+    };
+
+    let mut rng = Rng::new(seed);
+
+    for case in 1..=cases {
+        let value = strategy.generate(&mut rng);
+
+        if fails(&value) {
+            let (minimal, steps) = shrink(strategy, value.clone(), &fails);
+            // NOTE: a property that panicked has nothing to explain.
+            let explanation =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| explain(&minimal)))
+                    .ok()
+                    .flatten()
+                    .map(|e| format!("\n{e}"))
+                    .unwrap_or_default();
+            panic!(
+                "property failed on case {case} (seed {seed}).\n\
+                original input: {value:?}\n\
+                shrunk input ({steps} steps): {minimal:?}{explanation}"
+            );
+        }
+    }
+}
+
+/// Checks that a naive and an optimized implementation agree on generated values.
+/// On failure, the panic message shows both results for the shrunk input.
+#[track_caller]
+pub fn check_equivalent<S: Strategy, T: PartialEq + Debug>(
+    strategy: &S,
+    naive: impl Fn(&S::Value) -> T,
+    optimized: impl Fn(&S::Value) -> T,
+) {
+    check_explained(
+        strategy,
+        |value| naive(value) == optimized(value),
+        |value| {
+            Some(format!(
+                "naive: {:?}, optimized: {:?}",
+                naive(value),
+                optimized(value)
+            ))
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_within_bounds() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let v = (3..=7u32).generate(&mut rng);
+            assert!((3..=7).contains(&v));
+            let v = (-5..=5i64).generate(&mut rng);
+            assert!((-5..=5).contains(&v));
+        }
+        assert!((0..=u64::MAX).generate(&mut rng) > 0);
+    }
+
+    #[test]
+    fn passing_property() {
+        check(&vec_of(0..=100u32, 0..=10), |v| v.len() <= 10);
+    }
+
+    #[test]
+    fn shrinks_to_minimal_counterexample() {
+        let strategy = vec_of(0..=1000u32, 0..=20);
+        let fails = |v: &Vec<u32>| v.iter().any(|x| *x >= 10);
+
+        let mut rng = Rng::new(7);
+        let value = std::iter::repeat_with(|| strategy.generate(&mut rng))
+            .find(fails)
+            .unwrap();
+
+        let (minimal, _) = shrink(&strategy, value, &fails);
+        assert_eq!(minimal, vec![10]);
+    }
+
+    #[test]
+    #[should_panic(expected = "naive: 1, optimized: 0")]
+    fn reports_disagreement() {
+        check_equivalent(&(0..=100u32, 0..=100u32), |(a, b)| a + b, |(a, _)| a * 2);
+    }
+}
@@ -2,15 +2,18 @@
 use std::{
     fmt::Display,
     process::{Command, Output, Stdio},
+    time::Duration,
 };
 
-use crate::template::Day;
+use crate::template::{aoc_year, event_length, format_countdown, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
     CommandNotFound,
     CommandNotCallable,
     BadExitStatus(Output),
+    NotInEvent(Day, u16),
+    Locked(Day, u16, Duration),
 }
 
 impl Display for AocCommandError {
@@ -21,6 +24,16 @@ impl Display for AocCommandError {
             AocCommandError::BadExitStatus(_) => {
                 write!(f, "aoc-cli exited with a non-zero status.")
             }
+            AocCommandError::NotInEvent(day, year) => write!(
+                f,
+                "day {day} is not part of the {year} event, which has {} days.",
+                event_length(*year)
+            ),
+            AocCommandError::Locked(day, year, left) => write!(
+                f,
+                "day {day} of {year} is not unlocked yet, it unlocks in {}.",
+                format_countdown(*left)
+            ),
         }
     }
 }
@@ -33,7 +46,24 @@ pub fn check() -> Result<(), AocCommandError> {
     Ok(())
 }
 
+/// Checks that the puzzle for `day` exists and has been unlocked in the configured year.
+pub fn check_available(day: Day) -> Result<(), AocCommandError> {
+    let Some(year) = aoc_year() else {
+        return Ok(());
+    };
+
+    if !day.is_in_event(year) {
+        return Err(AocCommandError::NotInEvent(day, year));
+    }
+
+    match day.time_until_unlock(year) {
+        Some(left) => Err(AocCommandError::Locked(day, year, left)),
+        None => Ok(()),
+    }
+}
+
 pub fn read(day: Day) -> Result<Output, AocCommandError> {
+    check_available(day)?;
     let puzzle_path = get_puzzle_path(day);
 
     let args = build_args(
@@ -50,6 +80,7 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
 }
 
 pub fn download(day: Day) -> Result<Output, AocCommandError> {
+    check_available(day)?;
     let input_path = get_input_path(day);
     let puzzle_path = get_puzzle_path(day);
 
@@ -73,6 +104,8 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
 }
 
 pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+    check_available(day)?;
+
     // workaround: the argument order is inverted for submit.
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
@@ -88,17 +121,10 @@ fn get_puzzle_path(day: Day) -> String {
     format!("data/puzzles/{day}.md")
 }
 
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
-}
-
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
     let mut cmd_args = args.to_vec();
 
-    if let Some(year) = get_year() {
+    if let Some(year) = aoc_year() {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
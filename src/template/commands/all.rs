@@ -1,5 +1,31 @@
-use crate::template::{all_days, run_multi::run_multi};
+use std::process;
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+use crate::template::run_multi::{run_multi, skip_slow_days};
+use crate::template::timings::Timings;
+use crate::template::{all_days, DaySet};
+
+pub fn handle(
+    days: Option<DaySet>,
+    is_release: bool,
+    only_part: Option<u8>,
+    skip_slow: Option<f64>,
+) {
+    let mut days_to_run = days.unwrap_or_else(|| all_days().collect());
+
+    if let Some(threshold) = skip_slow {
+        let stored_timings = match Timings::read_from_file() {
+            Ok(timings) => timings,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        };
+        days_to_run = skip_slow_days(&days_to_run, &stored_timings, threshold);
+    }
+
+    let (_, failures) = run_multi(&days_to_run, is_release, false, only_part);
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
 }
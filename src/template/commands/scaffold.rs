@@ -9,6 +9,14 @@ use crate::template::Day;
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
+const MANIFEST_TEMPLATE: &str = r#"{
+    "cases": [
+        { "part": 1, "expected": null },
+        { "part": 2, "expected": null }
+    ]
+}
+"#;
+
 fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
     let mut file = OpenOptions::new();
     if overwrite {
@@ -30,6 +38,7 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
 pub fn handle(day: Day, overwrite: bool) {
     let input_path = format!("data/inputs/{day}.txt");
     let example_path = format!("data/examples/{day}.txt");
+    let manifest_path = format!("data/examples/{day}.json");
     let module_path = format!("src/bin/{day}.rs");
 
     let mut file = match safe_create_file(&module_path, overwrite) {
@@ -74,6 +83,18 @@ pub fn handle(day: Day, overwrite: bool) {
         }
     }
 
+    match safe_create_file(&manifest_path, overwrite)
+        .and_then(|mut file| file.write_all(MANIFEST_TEMPLATE.as_bytes()))
+    {
+        Ok(()) => {
+            println!("Created example manifest \"{}\"", &manifest_path);
+        }
+        Err(e) => {
+            eprintln!("Failed to create example manifest: {e}");
+            process::exit(1);
+        }
+    }
+
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
 }
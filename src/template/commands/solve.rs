@@ -2,7 +2,45 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Flags of the `solve` command that the solution reads from its own arguments.
+#[derive(Debug, Clone, Default)]
+pub struct SolutionArgs {
+    pub submit_part: Option<u8>,
+    pub visualize: bool,
+    pub export: Option<String>,
+    pub verbose: bool,
+    /// Everything after `--`, passed on unchanged, e.g. `--detector variance` for day 14.
+    pub trailing: Vec<String>,
+}
+
+impl SolutionArgs {
+    fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if let Some(submit_part) = self.submit_part {
+            args.push("--submit".to_string());
+            args.push(submit_part.to_string());
+        }
+
+        if self.visualize {
+            args.push("--visualize".to_string());
+        }
+
+        if self.verbose {
+            args.push("--verbose".to_string());
+        }
+
+        if let Some(export) = &self.export {
+            args.push("--export".to_string());
+            args.push(export.clone());
+        }
+
+        args.extend(self.trailing.iter().cloned());
+        args
+    }
+}
+
+pub fn handle(day: Day, release: bool, dhat: bool, solution_args: &SolutionArgs) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -17,11 +55,7 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
     }
 
     cmd_args.push("--".to_string());
-
-    if let Some(submit_part) = submit_part {
-        cmd_args.push("--submit".to_string());
-        cmd_args.push(submit_part.to_string());
-    }
+    cmd_args.extend(solution_args.to_args());
 
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
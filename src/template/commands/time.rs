@@ -1,31 +1,57 @@
-use std::collections::HashSet;
+use std::process;
 
-use crate::template::run_multi::run_multi;
+use crate::template::run_multi::{run_multi, skip_slow_days};
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
-
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
-
-    let days_to_run = day.map_or_else(
-        || {
-            if run_all {
-                all_days().collect()
-            } else {
-                // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
-                    .collect()
-            }
-        },
-        |day| HashSet::from([day]),
-    );
+use crate::template::{all_days, readme_benchmarks, DaySet};
+
+pub fn handle(
+    days: Option<DaySet>,
+    run_all: bool,
+    store: bool,
+    only_part: Option<u8>,
+    skip_slow: Option<f64>,
+) {
+    // NOTE: the stored total of a day covers both parts and cannot be updated from a single part.
+    if store && only_part.is_some() {
+        eprintln!("`--store` cannot be combined with `--only-part`.");
+        process::exit(1);
+    }
+
+    // NOTE: abort instead of falling back to empty timings, a subsequent `--store` would otherwise discard the history.
+    let stored_timings = match Timings::read_from_file() {
+        Ok(timings) => timings,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            eprintln!("Fix or remove the file, or run `cargo time --check` for details.");
+            process::exit(1);
+        }
+    };
+
+    let mut days_to_run = days.unwrap_or_else(|| {
+        if run_all {
+            all_days().collect()
+        } else {
+            // when the `--all` flag is not set, filter out days that are fully benched.
+            all_days()
+                .filter(|day| !stored_timings.is_day_complete(*day))
+                .collect()
+        }
+    });
+
+    if let Some(threshold) = skip_slow {
+        days_to_run = skip_slow_days(&days_to_run, &stored_timings, threshold);
+    }
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let (timings, failures) = run_multi(&days_to_run, true, true, only_part);
+    let timings = timings.unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+
+        if let Err(e) = merged_timings.store_file() {
+            eprintln!("Failed to store timings: {e}");
+            process::exit(1);
+        }
 
         println!();
         match readme_benchmarks::update(merged_timings) {
@@ -37,4 +63,24 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
         }
     }
+
+    if !failures.is_empty() {
+        process::exit(1);
+    }
+}
+
+/// Validate the stored timings file without running any solutions.
+pub fn check() {
+    match Timings::read_from_file() {
+        Ok(timings) => {
+            println!(
+                "Timings file is valid ({} day(s) recorded).",
+                timings.data.len()
+            );
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+    }
 }
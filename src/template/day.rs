@@ -1,13 +1,20 @@
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[cfg(feature = "today")]
 use chrono::{Datelike, FixedOffset, Utc};
 
-#[cfg(feature = "today")]
 const SERVER_UTC_OFFSET: i32 = -5;
 
+/// The last day of a full event, days are numbered from 1.
+const LAST_DAY: u8 = 25;
+
+/// The first year in which the event was shortened from 25 to 12 puzzles.
+const SHORT_EVENT_SINCE: u16 = 2025;
+
 /// A valid day number of advent (i.e. an integer in range 1 to 25).
 ///
 /// # Display
@@ -25,7 +32,7 @@ impl Day {
     /// Creates a [`Day`] from the provided value if it's in the valid range,
     /// returns [`None`] otherwise.
     pub fn new(day: u8) -> Option<Self> {
-        if day == 0 || day > 25 {
+        if day == 0 || day > LAST_DAY {
             return None;
         }
         Some(Self(day))
@@ -45,11 +52,12 @@ impl Day {
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
+    /// Returns the current day if it's a day of this year's event in december, `None` otherwise.
     pub fn today() -> Option<Self> {
         let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
         let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
+        let year = u16::try_from(today.year()).ok()?;
+        if today.month() == 12 && today.day() <= u32::from(event_length(year)) {
             Self::new(u8::try_from(today.day()).ok()?)
         } else {
             None
@@ -57,6 +65,86 @@ impl Day {
     }
 }
 
+impl Day {
+    /// Returns `true` if the day is part of the event held in `year`.
+    pub fn is_in_event(self, year: u16) -> bool {
+        self.0 <= event_length(year)
+    }
+
+    /// Returns the moment the puzzle for this day unlocks (midnight in the server's timezone).
+    pub fn unlock_time(self, year: u16) -> SystemTime {
+        let days = days_from_civil(i64::from(year), 12, i64::from(self.0));
+        let secs = days * 86_400 - i64::from(SERVER_UTC_OFFSET) * 3600;
+        UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).unwrap_or(0))
+    }
+
+    /// Returns `true` if the puzzle for this day has been unlocked in `year`.
+    pub fn is_unlocked(self, year: u16) -> bool {
+        self.time_until_unlock(year).is_none()
+    }
+
+    /// Returns the time left until the puzzle for this day unlocks, [`None`] if it is already available.
+    pub fn time_until_unlock(self, year: u16) -> Option<Duration> {
+        self.unlock_time(year)
+            .duration_since(SystemTime::now())
+            .ok()
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// Returns the number of puzzles in the event held in `year`.
+pub fn event_length(year: u16) -> u8 {
+    if year >= SHORT_EVENT_SINCE {
+        12
+    } else {
+        LAST_DAY
+    }
+}
+
+/// Returns the event year configured via the `AOC_YEAR` environment variable.
+pub fn aoc_year() -> Option<u16> {
+    std::env::var("AOC_YEAR").ok()?.parse().ok()
+}
+
+/// Returns the next puzzle of the event held in `year` that is still locked, and the time left until it unlocks.
+pub fn next_unlock(year: u16) -> Option<(Day, Duration)> {
+    all_days()
+        .take_while(|day| day.is_in_event(year))
+        .find_map(|day| day.time_until_unlock(year).map(|left| (day, left)))
+}
+
+/// Formats a countdown as e.g. `2d 03h 04m 05s`, omitting leading zero units.
+pub fn format_countdown(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, seconds) = (
+        secs / 86_400,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+
+    if days > 0 {
+        format!("{days}d {hours:02}h {minutes:02}m {seconds:02}s")
+    } else if hours > 0 {
+        format!("{hours}h {minutes:02}m {seconds:02}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// Number of days between 1970-01-01 and the given date of the proleptic gregorian calendar.
+/// see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
 impl Display for Day {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:02}", self.0)
@@ -94,7 +182,106 @@ impl Error for DayFromStrError {}
 
 impl Display for DayFromStrError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("expecting a day number between 1 and 25")
+        write!(f, "expecting a day number between 1 and {LAST_DAY}")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A sorted set of days, e.g. parsed from a command-line argument like `1-5,8,10..`.
+///
+/// Each comma-separated item is either a single day or an inclusive range written as `1-5` or `1..5`.
+/// Open ranges like `10..` or `..5` extend to the first or last day of the event (see [`event_length`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DaySet(BTreeSet<Day>);
+
+impl DaySet {
+    /// Parses a day set, resolving open ranges against the event held in `year`.
+    /// Without a year, open ranges extend to the 25th.
+    pub fn parse_for_year(s: &str, year: Option<u16>) -> Result<Self, DaySetFromStrError> {
+        let last_day = year.map_or(LAST_DAY, event_length);
+        let mut days = BTreeSet::new();
+
+        for item in s.split(',').map(str::trim) {
+            let err = || DaySetFromStrError(item.to_string());
+
+            let bounds = item.split_once("..").or_else(|| item.split_once('-'));
+
+            let (start, end) = match bounds {
+                Some((start, end)) => {
+                    let start = match start.trim() {
+                        "" => 1,
+                        x => x.parse().map_err(|_| err())?,
+                    };
+                    let end = match end.trim().trim_start_matches('=') {
+                        "" => last_day,
+                        x => x.parse().map_err(|_| err())?,
+                    };
+                    (start, end)
+                }
+                None => {
+                    let day = item.parse().map_err(|_| err())?;
+                    (day, day)
+                }
+            };
+
+            if start > end {
+                return Err(err());
+            }
+
+            for day in start..=end {
+                days.insert(Day::new(day).ok_or_else(err)?);
+            }
+        }
+
+        Ok(Self(days))
+    }
+
+    pub fn contains(&self, day: Day) -> bool {
+        self.0.contains(&day)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates the days in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Day> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<Day> for DaySet {
+    fn from_iter<T: IntoIterator<Item = Day>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl FromStr for DaySet {
+    type Err = DaySetFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_for_year(s, aoc_year())
+    }
+}
+
+/// An error which can be returned when parsing a [`DaySet`].
+#[derive(Debug)]
+pub struct DaySetFromStrError(String);
+
+impl Error for DaySetFromStrError {}
+
+impl Display for DaySetFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid day selection `{}`, expecting days between 1 and {LAST_DAY} or ranges like `1-5` and `10..`",
+            self.0
+        )
     }
 }
 
@@ -121,10 +308,10 @@ impl Iterator for AllDays {
     type Item = Day;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 25 {
+        if self.current > LAST_DAY {
             return None;
         }
-        // NOTE: the iterator starts at 1 and we have verified that the value is not above 25.
+        // NOTE: the iterator starts at 1 and we have verified that the value is not above `LAST_DAY`.
         let day = Day(self.current);
         self.current += 1;
 
@@ -154,7 +341,64 @@ macro_rules! day {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{all_days, Day};
+    use super::{all_days, event_length, next_unlock, Day, DaySet};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn event_length_by_year() {
+        assert_eq!(event_length(2015), 25);
+        assert_eq!(event_length(2024), 25);
+        assert_eq!(event_length(2025), 12);
+        assert_eq!(Day(12).is_in_event(2025), true);
+        assert_eq!(Day(13).is_in_event(2025), false);
+    }
+
+    #[test]
+    fn unlock_time() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(
+            Day(1).unlock_time(2024),
+            UNIX_EPOCH + Duration::from_secs(1_733_029_200)
+        );
+        // 2020-12-25T05:00:00Z
+        assert_eq!(
+            Day(25).unlock_time(2020),
+            UNIX_EPOCH + Duration::from_secs(1_608_872_400)
+        );
+        assert_eq!(Day(1).is_unlocked(2015), true);
+        assert_eq!(Day(1).is_unlocked(9999), false);
+        assert_eq!(next_unlock(2015), None);
+        assert_eq!(next_unlock(9999).map(|(day, _)| day), Some(Day(1)));
+    }
+
+    #[test]
+    fn parses_day_sets() {
+        let days = |s: &str, year| {
+            DaySet::parse_for_year(s, year)
+                .unwrap()
+                .iter()
+                .map(Day::into_inner)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(days("3", None), vec![3]);
+        assert_eq!(days("1-3,8", None), vec![1, 2, 3, 8]);
+        assert_eq!(days("5..7, 6", None), vec![5, 6, 7]);
+        assert_eq!(days("..2", None), vec![1, 2]);
+        assert_eq!(days("24..", None), vec![24, 25]);
+        assert_eq!(days("10..", Some(2025)), vec![10, 11, 12]);
+        assert_eq!(days("1..=2", None), vec![1, 2]);
+    }
+
+    #[test]
+    fn rejects_invalid_day_sets() {
+        assert!(DaySet::parse_for_year("", None).is_err());
+        assert!(DaySet::parse_for_year("0", None).is_err());
+        assert!(DaySet::parse_for_year("1,26", None).is_err());
+        assert!(DaySet::parse_for_year("5-3", None).is_err());
+        assert!(DaySet::parse_for_year("1-a", None).is_err());
+        assert!(DaySet::parse_for_year("1,,2", None).is_err());
+    }
 
     #[test]
     fn all_days_iterator() {
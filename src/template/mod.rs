@@ -1,4 +1,6 @@
-use std::{env, fs};
+use std::{convert::Infallible, env, fmt::Display, fs};
+
+use runner::Answer;
 
 pub mod aoc_cli;
 pub mod commands;
@@ -36,9 +38,60 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
+/// Compares the answer of a solution part against the expected answer in its displayed form.
+/// A part that returns an error fails with the error message.
+#[track_caller]
+pub fn assert_answer<R: Answer>(result: R, expected: Option<&str>) {
+    match result.into_answer() {
+        Ok(result) => assert_eq!(result.map(|r| r.to_string()).as_deref(), expected),
+        Err(e) => panic!("solution returned an error: {e}"),
+    }
+}
+
+/// The parse step of the example tests of solutions without a `parse` function,
+/// the parts get the input as-is.
+pub fn unparsed(input: &str) -> Result<String, Infallible> {
+    Ok(input.to_string())
+}
+
+/// Runs the parse step of a solution on an example input, used by the generated example tests.
+#[track_caller]
+pub fn parse_example<'a, P, E: Display>(
+    parse: impl FnOnce(&'a str) -> Result<P, E>,
+    input: &'a str,
+) -> P {
+    parse(input).unwrap_or_else(|e| panic!("example could not be parsed: {e}"))
+}
+
+/// Expands into one test per case of the day's example manifest in `data/examples/<day>.json`.
+///
+/// The tests are generated by the build script and call the solution functions in scope,
+/// so the macro is meant to be invoked in the `tests` module of a solution.
+#[macro_export]
+macro_rules! example_tests {
+    () => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/example_tests_",
+            env!("CARGO_BIN_NAME"),
+            ".rs"
+        ));
+    };
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// By default, both parts take the input as `&str`. With `parse = <function>`, the input is
+/// parsed once by a function `fn(&str) -> Result<T, E>` and both parts take `&T` instead.
+/// The parse time is then reported separately from the solve time of the parts:
+///
+/// ```ignore
+/// advent_of_code::solution!(17, parse = Machine::parse);
+///
+/// pub fn part_one(machine: &Machine) -> Option<String> { ... }
+/// ```
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,8 +103,56 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, parse = $parse:path) => {
+        $crate::solution!(@parsed $day, $parse, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, 1, parse = $parse:path) => {
+        $crate::solution!(@parsed $day, $parse, [part_one, 1]);
+    };
+    ($day:expr, 2, parse = $parse:path) => {
+        $crate::solution!(@parsed $day, $parse, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@common $day, $crate::template::unparsed);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            $(
+                if is_part_selected($part) {
+                    run_part($func, &input, DAY, $part);
+                }
+            )*
+        }
+    };
+
+    (@parsed $day:expr, $parse:path, $( [$func:expr, $part:expr] )*) => {
+        $crate::solution!(@common $day, $parse);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            match run_parse($parse, &input) {
+                Ok(parsed) => {
+                    $(
+                        if is_part_selected($part) {
+                            run_part($func, &parsed, DAY, $part);
+                        }
+                    )*
+                }
+                Err(e) => {
+                    $(
+                        if is_part_selected($part) {
+                            print_parse_failure(&e, $part);
+                        }
+                    )*
+                }
+            }
+        }
+    };
+
+    (@common $day:expr, $parse:path) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +160,13 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
-        fn main() {
-            use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+        /// The parse step of the generated example tests.
+        #[cfg(test)]
+        #[allow(unused_macros)]
+        macro_rules! example_parser {
+            () => {
+                $parse
+            };
         }
     };
 }
@@ -1,49 +1,82 @@
-use std::{collections::HashSet, io};
+use std::io;
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{Day, DaySet, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
-use super::{
-    all_days,
-    timings::{Timing, Timings},
-};
+use super::timings::{Timing, Timings};
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// A solution part that returned an error instead of an answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub day: Day,
+    pub part: u8,
+    pub message: String,
+}
+
+/// Runs the solutions of `days_to_run`, returning their timings (if `is_timed`) and the parts that failed.
+pub fn run_multi(
+    days_to_run: &DaySet,
+    is_release: bool,
+    is_timed: bool,
+    only_part: Option<u8>,
+) -> (Option<Timings>, Vec<Failure>) {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut failures: Vec<Failure> = vec![];
 
     let mut need_space = false;
 
-    // NOTE: use non-duplicate, sorted day values.
-    all_days()
-        .filter(|day| days_to_run.contains(day))
-        .for_each(|day| {
-            if need_space {
-                println!();
-            }
-            need_space = true;
+    days_to_run.iter().for_each(|day| {
+        if need_space {
+            println!();
+        }
+        need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let output = child_commands::run_solution(day, is_timed, is_release, only_part).unwrap();
 
-            if output.is_empty() {
-                println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
-            }
-        });
+        if output.is_empty() {
+            println!("Not solved.");
+        } else {
+            let val = child_commands::parse_exec_time(&output, day);
+            timings.push(val);
+            failures.extend(child_commands::parse_failures(&output, day));
+        }
+    });
 
-    if is_timed {
+    let timings = is_timed.then(|| {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
         );
-        Some(timings)
-    } else {
-        None
+        timings
+    });
+
+    if !failures.is_empty() {
+        println!("\n{ANSI_BOLD}Failed parts:{ANSI_RESET}");
+        for failure in &failures {
+            println!(
+                "Day {} part {}: {}",
+                failure.day, failure.part, failure.message
+            );
+        }
     }
+
+    (timings, failures)
+}
+
+/// Removes days whose stored timing exceeds `threshold_millis` from the selection.
+pub fn skip_slow_days(days: &DaySet, timings: &Timings, threshold_millis: f64) -> DaySet {
+    days.iter()
+        .filter(|day| {
+            let is_slow = timings.is_day_slower_than(*day, threshold_millis);
+            if is_slow {
+                println!("{ANSI_ITALIC}Skipping day {day}, stored timing exceeds {threshold_millis}ms.{ANSI_RESET}");
+            }
+            !is_slow
+        })
+        .collect()
 }
 
 #[allow(dead_code)]
@@ -67,8 +100,8 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, Error, Failure};
+    use crate::template::{runner::ERROR_MARKER, Day};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -77,7 +110,12 @@ pub mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        only_part: Option<u8>,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -90,10 +128,21 @@ pub mod child_commands {
             args.push("--release");
         }
 
+        let mut child_args = vec![];
+
         if is_timed {
             // mirror `--time` flag to child invocations.
+            child_args.push("--time".to_string());
+        }
+
+        if let Some(part) = only_part {
+            child_args.push("--only-part".to_string());
+            child_args.push(part.to_string());
+        }
+
+        if !child_args.is_empty() {
             args.push("--");
-            args.push("--time");
+            args.extend(child_args.iter().map(String::as_str));
         }
 
         // spawn child command with piped stdout/stderr.
@@ -164,6 +213,24 @@ pub mod child_commands {
         timings
     }
 
+    /// Collects the parts that printed an error instead of an answer.
+    pub fn parse_failures(output: &[String], day: Day) -> Vec<Failure> {
+        output
+            .iter()
+            .filter_map(|l| {
+                // intermediate output is overwritten with a carriage return.
+                let l = l.rsplit('\r').next()?;
+                let (part, message) = l.split_once(ERROR_MARKER)?;
+                let part = part.trim().strip_prefix("Part ")?.strip_suffix(':')?;
+                Some(Failure {
+                    day,
+                    part: part.parse().ok()?,
+                    message: message.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,7 +241,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -205,7 +272,7 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_failures};
 
         use crate::day;
 
@@ -239,6 +306,35 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn adds_parse_time_to_total() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: (1.5µs @ 1000 samples)".into(),
+                    "Part 1: 42 (10.0µs @ 1000 samples)".into(),
+                    "Part 2: 43 (20.0µs @ 1000 samples)".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 31500_f64);
+            assert_eq!(res.part_1.unwrap(), "10.0µs");
+            assert_eq!(res.part_2.unwrap(), "20.0µs");
+        }
+
+        #[test]
+        fn parses_failures() {
+            let res = parse_failures(
+                &[
+                    "Part 1: 42 (1.0ms)".into(),
+                    "Part 2: ✖\rPart 2: ✖ error: line 3, column 7: expected an integer".into(),
+                ],
+                day!(5),
+            );
+            assert_eq!(res.len(), 1);
+            assert_eq!(res[0].part, 2);
+            assert_eq!(res[0].message, "line 3, column 7: expected an integer");
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
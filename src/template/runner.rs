@@ -9,7 +9,58 @@ use std::{cmp, env, process};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Marks a failed part in the output of a solution, see `run_multi`.
+pub const ERROR_MARKER: &str = "✖ error: ";
+
+/// Values a solution part can return.
+///
+/// A part returns its answer directly, as `Option` (`None` = not solved yet)
+/// or as `Result`, where an error is reported with its message instead of the answer.
+pub trait Answer {
+    type Value: Display;
+
+    fn into_answer(self) -> Result<Option<Self::Value>, String>;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {$(
+        impl Answer for $t {
+            type Value = $t;
+
+            fn into_answer(self) -> Result<Option<$t>, String> {
+                Ok(Some(self))
+            }
+        }
+    )*};
+}
+
+impl_answer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String);
+
+impl<'a> Answer for &'a str {
+    type Value = &'a str;
+
+    fn into_answer(self) -> Result<Option<&'a str>, String> {
+        Ok(Some(self))
+    }
+}
+
+impl<T: Display> Answer for Option<T> {
+    type Value = T;
+
+    fn into_answer(self) -> Result<Option<T>, String> {
+        Ok(self)
+    }
+}
+
+impl<T: Answer, E: Display> Answer for Result<T, E> {
+    type Value = T::Value;
+
+    fn into_answer(self) -> Result<Option<T::Value>, String> {
+        self.map_err(|e| e.to_string())?.into_answer()
+    }
+}
+
+pub fn run_part<I: Clone, R: Answer>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
     let (result, duration, samples) =
@@ -17,19 +68,67 @@ pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, d
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
-    if let Some(result) = result {
+    if let Ok(Some(result)) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Runs the shared parse step of a solution, see `solution!`.
+///
+/// The parse time is printed on its own line, so the time of each part only covers solving.
+/// Like a part, the parse step is benched when running with `--time`.
+pub fn run_parse<'a, P, E: Display>(
+    parse: impl Fn(&'a str) -> Result<P, E>,
+    input: &'a str,
+) -> Result<P, String> {
+    print!("Parse:");
+    let _ = stdout().flush();
+
+    let timer = Instant::now();
+    let result = parse(input).map_err(|e| e.to_string());
+    let base_time = timer.elapsed();
+
+    let (duration, samples) = if result.is_ok() && env::args().any(|x| x == "--time") {
+        bench(&parse, input, &base_time)
+    } else {
+        (base_time, 1)
+    };
+
+    print!("\r");
+    match &result {
+        Ok(_) => println!("Parse:{}", format_duration(&duration, samples)),
+        Err(e) => println!("Parse: {ERROR_MARKER}{e}"),
+    }
+
+    result
+}
+
+/// Reports a part that did not run because the input could not be parsed.
+pub fn print_parse_failure(message: &str, part: u8) {
+    println!("Part {part}: {ERROR_MARKER}{message}");
+}
+
+/// Returns `false` if the binary was invoked with `--only-part` for the other part.
+pub fn is_part_selected(part: u8) -> bool {
+    let args: Vec<String> = env::args().collect();
+
+    args.iter()
+        .position(|x| x == "--only-part")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|x| x.parse::<u8>().ok())
+        .is_none_or(|only_part| only_part == part)
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
+///
+/// Failed parts are not benched.
+fn run_timed<I: Clone, R: Answer>(
+    func: impl Fn(I) -> R,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    hook: impl Fn(&Result<Option<R::Value>, String>),
+) -> (Result<Option<R::Value>, String>, Duration, u128) {
     let timer = Instant::now();
     let result = {
         let input = input.clone();
@@ -40,10 +139,11 @@ fn run_timed<I: Clone, T>(
         func(input)
     };
     let base_time = timer.elapsed();
+    let result = result.into_answer();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let run = if result.is_ok() && std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
@@ -94,11 +194,11 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: &Result<Option<T>, String>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
+        Ok(Some(result)) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
@@ -118,7 +218,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Ok(None) => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -126,6 +226,14 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Err(e) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: {ERROR_MARKER}{e}");
+            }
+        }
     }
 }
 
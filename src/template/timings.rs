@@ -1,9 +1,15 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fmt::Display, fs, io, path::Path, str::FromStr};
 use tinyjson::JsonValue;
 
 use crate::template::Day;
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+static TIMINGS_BACKUP_PATH: &str = "./data/timings.json.bak";
+
+/// Version of the `timings.json` schema written by this template.
+/// Files without a `version` key are read as version `0`, which shares the layout of version 1.
+/// They are accepted as they are and only get a `version` key the next time timings are stored.
+pub const TIMINGS_SCHEMA_VERSION: u32 = 1;
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -21,20 +27,62 @@ pub struct Timings {
     pub data: Vec<Timing>,
 }
 
+#[derive(Debug)]
+pub enum Error {
+    IO(io::Error),
+    Parser(String),
+    UnsupportedVersion(u32),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::IO(e) => write!(f, "could not access {TIMINGS_FILE_PATH}: {e}"),
+            Error::Parser(e) => write!(f, "{TIMINGS_FILE_PATH} is malformed: {e}"),
+            Error::UnsupportedVersion(v) => write!(
+                f,
+                "{TIMINGS_FILE_PATH} has schema version {v}, this template supports up to version {TIMINGS_SCHEMA_VERSION}."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl From<String> for Error {
+    fn from(e: String) -> Self {
+        Error::Parser(e)
+    }
+}
+
 impl Timings {
     /// Dehydrate timings to a JSON file.
+    /// An existing file is copied to `timings.json.bak` before it is overwritten.
     pub fn store_file(&self) -> Result<(), Error> {
+        if Path::new(TIMINGS_FILE_PATH).exists() {
+            fs::copy(TIMINGS_FILE_PATH, TIMINGS_BACKUP_PATH)?;
+        }
+
         let json = JsonValue::from(self.clone());
         let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
-        json.format_to(&mut file)
+        json.format_to(&mut file)?;
+        Ok(())
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
-            .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+    /// A file that is present but cannot be parsed is reported as an error instead of being discarded.
+    pub fn read_from_file() -> Result<Self, Error> {
+        match fs::read_to_string(TIMINGS_FILE_PATH) {
+            Ok(contents) => Timings::try_from(contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Timings::default()),
+            Err(e) => Err(Error::IO(e)),
+        }
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -51,7 +99,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -65,6 +113,13 @@ impl Timings {
             .iter()
             .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
     }
+
+    /// Returns `true` if the stored timing of `day` exceeds `threshold_millis`.
+    pub fn is_day_slower_than(&self, day: Day, threshold_millis: f64) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.day == day && t.total_nanos / 1_000_000_f64 > threshold_millis)
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -73,6 +128,11 @@ impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert(
+            "version".into(),
+            JsonValue::Number(f64::from(TIMINGS_SCHEMA_VERSION)),
+        );
+
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
@@ -83,25 +143,52 @@ impl From<Timings> for JsonValue {
 }
 
 impl TryFrom<String> for Timings {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+        let json = JsonValue::from_str(&value)
+            .map_err(|e| Error::Parser(format!("not a valid JSON file ({e}).")))?;
 
-        let json_data = json
+        let json = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.".to_string())?;
+
+        // NOTE: files written before the schema was versioned have no `version` key, but share the layout of version 1.
+        let version = match json.get("version") {
+            None => 0,
+            Some(v) => v
+                .get::<f64>()
+                .filter(|v| v.fract() == 0.0 && **v >= 0.0)
+                .map(|v| *v as u32)
+                .ok_or("expected `json.version` to be a non-negative integer.".to_string())?,
+        };
+
+        if version > TIMINGS_SCHEMA_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let json_data = json
             .get("data")
-            .ok_or("expected JSON document to have key `data`.")?
+            .ok_or("expected JSON document to have key `data`.".to_string())?
             .get::<Vec<JsonValue>>()
-            .ok_or("expected `json.data` to be an array.")?;
+            .ok_or("expected `json.data` to be an array.".to_string())?;
 
-        Ok(Timings {
-            data: json_data
-                .iter()
-                .map(Timing::try_from)
-                .collect::<Result<_, _>>()?,
-        })
+        let data: Vec<Timing> = json_data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Timing::try_from(v).map_err(|e| format!("`json.data[{i}]`: {e}")))
+            .collect::<Result<_, _>>()?;
+
+        for (i, timing) in data.iter().enumerate() {
+            if data[..i].iter().any(|t| t.day == timing.day) {
+                return Err(Error::Parser(format!(
+                    "`json.data[{i}]`: duplicate entry for day {}.",
+                    timing.day
+                )));
+            }
+        }
+
+        Ok(Timings { data })
     }
 }
 
@@ -209,7 +296,10 @@ mod tests {
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{
+            day,
+            template::timings::{Error, Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
@@ -237,6 +327,34 @@ mod tests {
             Timings::try_from(json).unwrap();
         }
 
+        #[test]
+        fn handles_versioned_timings() {
+            let json = r#"{ "version": 1, "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data.len(), 1);
+        }
+
+        #[test]
+        fn errors_for_unsupported_version() {
+            let json = r#"{ "version": 99, "data": [] }"#.to_string();
+            let err = Timings::try_from(json).unwrap_err();
+            assert!(matches!(err, Error::UnsupportedVersion(99)));
+        }
+
+        #[test]
+        fn errors_for_duplicate_days() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": null, "part_2": null, "total_nanos": 0 }, { "day": "01", "part_1": null, "part_2": null, "total_nanos": 0 }] }"#.to_string();
+            let err = Timings::try_from(json).unwrap_err();
+            assert!(err.to_string().contains("duplicate entry for day 01"));
+        }
+
+        #[test]
+        fn reports_index_of_malformed_timing() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": null, "part_2": null, "total_nanos": 0 }, { "day": "26" }] }"#.to_string();
+            let err = Timings::try_from(json).unwrap_err();
+            assert!(err.to_string().contains("`json.data[1]`"));
+        }
+
         #[test]
         #[should_panic]
         fn panics_for_malformed_timings() {
@@ -247,9 +365,30 @@ mod tests {
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::timings::{Timings, TIMINGS_SCHEMA_VERSION};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
+        #[test]
+        fn serializes_schema_version() {
+            let value = JsonValue::from(get_mock_timings());
+            let version = value
+                .get::<HashMap<String, JsonValue>>()
+                .unwrap()
+                .get("version")
+                .unwrap()
+                .get::<f64>()
+                .copied();
+            assert_eq!(version, Some(f64::from(TIMINGS_SCHEMA_VERSION)));
+        }
+
+        #[test]
+        fn round_trips_timings() {
+            let json = JsonValue::from(get_mock_timings()).stringify().unwrap();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.data.len(), 3);
+        }
+
         #[test]
         fn serializes_timings() {
             let timings = get_mock_timings();
@@ -285,7 +424,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -299,7 +438,7 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -313,7 +452,26 @@ mod tests {
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
+        }
+    }
+
+    mod is_day_slower_than {
+        use crate::day;
+
+        use super::get_mock_timings;
+
+        #[test]
+        fn compares_total_time() {
+            let timings = get_mock_timings();
+            assert_eq!(timings.is_day_slower_than(day!(1), 29_999.0), true);
+            assert_eq!(timings.is_day_slower_than(day!(1), 30_000.0), false);
+        }
+
+        #[test]
+        fn handles_missing_days() {
+            let timings = get_mock_timings();
+            assert_eq!(timings.is_day_slower_than(day!(3), 0.0), false);
         }
     }
 
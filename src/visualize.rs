@@ -0,0 +1,574 @@
+//! Frames of grid based simulations and a terminal player to step through them.
+//!
+//! A solution records [`Frame`]s into an [`Animation`] while it runs and plays it at the end.
+//! Recording is a no-op unless the solution runs with `--visualize` (`cargo solve <day> --visualize`),
+//! so solving and timing are not slowed down by the frames:
+//!
+//! ```ignore
+//! use advent_of_code::visualize::{Animation, Frame};
+//! use colored::Color;
+//!
+//! let mut animation = Animation::new();
+//! for step in 0..100 {
+//!     // ...
+//!     animation.record(|| {
+//!         Frame::from_grid(&grid, |_, c| *c)
+//!             .mark(robot, '@', Color::Yellow)
+//!             .with_caption(format!("step {step}"))
+//!     });
+//! }
+//! animation.play();
+//! ```
+use std::env;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, stdin, stdout, BufRead, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use colored::{Color, Colorize};
+
+use crate::grid::{Direction, Grid, Point};
+use crate::image::{self, Image, ImageFormat, Rgb};
+
+/// Returns `true` if the solution was started with `--visualize`. Never records while timing.
+pub fn is_enabled() -> bool {
+    env::args().any(|x| x == "--visualize") && !env::args().any(|x| x == "--time")
+}
+
+/// A character on a frame with an optional color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    pub const fn new(ch: char) -> Self {
+        Self { ch, color: None }
+    }
+}
+
+/// A snapshot of a grid with overlays, the unit of an [`Animation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    cells: Grid<Cell>,
+    caption: String,
+}
+
+impl Frame {
+    /// Renders every cell of `grid` with `f`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut f: impl FnMut(Point, &T) -> char) -> Self {
+        let cells = grid
+            .iter()
+            .map(|(p, value)| Cell::new(f(p, value)))
+            .collect();
+        Self {
+            cells: Grid::from_vec(grid.width(), grid.height(), cells),
+            caption: String::new(),
+        }
+    }
+
+    /// A `width` x `height` frame filled with `.`.
+    pub fn blank(width: usize, height: usize) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::new('.')),
+            caption: String::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn cells(&self) -> &Grid<Cell> {
+        &self.cells
+    }
+
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    /// Draws `ch` at `p`, points outside of the frame are ignored.
+    pub fn draw(&mut self, p: Point, ch: char, color: Option<Color>) {
+        self.cells.set(p, Cell { ch, color });
+    }
+
+    /// Draws `ch` in `color` at `p`.
+    pub fn mark(mut self, p: Point, ch: char, color: Color) -> Self {
+        self.draw(p, ch, Some(color));
+        self
+    }
+
+    /// Colors the cells at `points` without changing their characters.
+    pub fn highlight(mut self, points: impl IntoIterator<Item = Point>, color: Color) -> Self {
+        for p in points {
+            if let Some(cell) = self.cells.get_mut(p) {
+                cell.color = Some(color);
+            }
+        }
+        self
+    }
+
+    /// Draws `path` with arrows pointing to the next point, the last point is drawn as `O`.
+    /// Repeated points (e.g. turning on the spot) are skipped, steps between points that are
+    /// not orthogonal neighbors are drawn as `*`.
+    pub fn path(mut self, path: impl IntoIterator<Item = Point>, color: Color) -> Self {
+        let mut path = path.into_iter().peekable();
+        while let Some(p) = path.next() {
+            while path.peek() == Some(&p) {
+                path.next();
+            }
+            let ch = match path.peek() {
+                None => 'O',
+                Some(&next) => Direction::ALL
+                    .into_iter()
+                    .find(|d| d.offset() == next - p)
+                    .map_or('*', Direction::arrow),
+            };
+            self.draw(p, ch, Some(color));
+        }
+        self
+    }
+
+    /// Renders the frame with colors for the terminal, followed by the caption.
+    pub fn colored(&self) -> String {
+        let mut out = String::new();
+        for row in self.cells.rows() {
+            for cell in row {
+                match cell.color {
+                    Some(color) => out.push_str(&cell.ch.to_string().color(color).to_string()),
+                    None => out.push(cell.ch),
+                }
+            }
+            out.push('\n');
+        }
+        if !self.caption.is_empty() {
+            out.push_str(&self.caption.italic().to_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the frame with `scale` x `scale` pixels per cell.
+    ///
+    /// Colored cells use their color. Other cells are black for `.` and spaces and white otherwise.
+    pub fn to_image(&self, scale: usize) -> Image {
+        Image::from_grid(&self.cells, scale, |_, cell| pixel(cell))
+    }
+}
+
+/// The plain characters, followed by the caption.
+impl Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cells.render(|_, cell| cell.ch))?;
+        if !self.caption.is_empty() {
+            writeln!(f, "{}", self.caption)?;
+        }
+        Ok(())
+    }
+}
+
+fn pixel(cell: &Cell) -> Rgb {
+    match (cell.color, cell.ch) {
+        (Some(color), _) => rgb(color),
+        (None, '.' | ' ') => image::BLACK,
+        (None, _) => image::WHITE,
+    }
+}
+
+/// The xterm default palette.
+fn rgb(color: Color) -> Rgb {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 0, 0],
+        Color::Green => [0, 205, 0],
+        Color::Yellow => [205, 205, 0],
+        Color::Blue => [0, 0, 238],
+        Color::Magenta => [205, 0, 205],
+        Color::Cyan => [0, 205, 205],
+        Color::White => [229, 229, 229],
+        Color::BrightBlack => [127, 127, 127],
+        Color::BrightRed => [255, 0, 0],
+        Color::BrightGreen => [0, 255, 0],
+        Color::BrightYellow => [255, 255, 0],
+        Color::BrightBlue => [92, 92, 255],
+        Color::BrightMagenta => [255, 0, 255],
+        Color::BrightCyan => [0, 255, 255],
+        Color::BrightWhite => [255, 255, 255],
+        Color::TrueColor { r, g, b } => [r, g, b],
+    }
+}
+
+/// Frames recorded by a solution, see the module documentation.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    enabled: bool,
+}
+
+impl Animation {
+    /// An animation that records frames if the solution runs with `--visualize`.
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            enabled: is_enabled(),
+        }
+    }
+
+    /// An animation of `frames` that always records.
+    pub fn from_frames(frames: Vec<Frame>) -> Self {
+        Self {
+            frames,
+            enabled: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Adds the frame built by `frame`, which is only called when recording.
+    pub fn record(&mut self, frame: impl FnOnce() -> Frame) {
+        if self.enabled {
+            self.frames.push(frame());
+        }
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Writes all frames as text to `path`, separated by blank lines.
+    pub fn export_text(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        for frame in &self.frames {
+            writeln!(out, "{frame}")?;
+        }
+        out.flush()
+    }
+
+    /// Writes every frame to `dir` as image `frame_0001.<ext>`, `frame_0002.<ext>`, ...
+    /// with `scale` x `scale` pixels per cell and returns the number of images.
+    pub fn export_images(
+        &self,
+        dir: impl AsRef<Path>,
+        format: ImageFormat,
+        scale: usize,
+    ) -> io::Result<usize> {
+        image::write_numbered(dir, self.frames.iter().map(|f| f.to_image(scale)), format)
+    }
+
+    /// Writes all frames into one contact sheet with `columns` columns, see [`image::contact_sheet`].
+    pub fn export_sheet(
+        &self,
+        path: impl AsRef<Path>,
+        columns: usize,
+        scale: usize,
+    ) -> io::Result<()> {
+        let images: Vec<Image> = self.frames.iter().map(|f| f.to_image(scale)).collect();
+        image::contact_sheet(&images, columns).save(path)
+    }
+
+    /// Opens the recorded frames in the terminal [`Player`], does nothing if there are none.
+    pub fn play(self) {
+        if !self.frames.is_empty() {
+            Player::new(self).run();
+        }
+    }
+}
+
+/// Pixels per cell of exported images.
+const IMAGE_SCALE: usize = 4;
+
+const DEFAULT_SHEET_COLUMNS: usize = 10;
+
+const DEFAULT_FPS: u32 = 10;
+
+const HELP: &str = "\
+<enter> | n [count]   next frame(s)
+p [count]            previous frame(s)
+g <frame>            go to frame
+play [fps]           play until the last frame, <enter> pauses
+text <file>          export all frames as text
+images <dir> [ext]   export all frames as png (default), ppm or pbm images
+sheet <file> [cols]  export all frames as one contact sheet
+q                    quit";
+
+/// A command of the [`Player`], entered as one line.
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Next(usize),
+    Previous(usize),
+    Seek(usize),
+    Play(u32),
+    ExportText(String),
+    ExportImages(String, ImageFormat),
+    ExportSheet(String, usize),
+    Help,
+    Quit,
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("n");
+        let arg = words.next();
+        let option = words.next();
+
+        let number = |default: usize| -> Result<usize, String> {
+            arg.map_or(Ok(default), |arg| {
+                arg.parse().map_err(|_| format!("{arg:?} is not a number"))
+            })
+        };
+        let required = |name: &str| arg.map(str::to_string).ok_or(format!("missing <{name}>"));
+
+        match command {
+            "n" | "next" => number(1).map(Command::Next),
+            "p" | "prev" => number(1).map(Command::Previous),
+            "g" | "goto" => arg
+                .ok_or("missing <frame>".to_string())
+                .and_then(|_| number(1))
+                .map(Command::Seek),
+            "play" => number(DEFAULT_FPS as usize).map(|fps| Command::Play(fps.max(1) as u32)),
+            "text" => required("file").map(Command::ExportText),
+            "images" => {
+                let format = match option {
+                    None => Ok(ImageFormat::Png),
+                    Some(ext) => ImageFormat::from_extension(ext)
+                        .ok_or(format!("{ext:?} is not png, ppm or pbm")),
+                };
+                Ok(Command::ExportImages(required("dir")?, format?))
+            }
+            "sheet" => {
+                let columns = option.map_or(Ok(DEFAULT_SHEET_COLUMNS), |columns| {
+                    columns
+                        .parse()
+                        .map_err(|_| format!("{columns:?} is not a number"))
+                });
+                Ok(Command::ExportSheet(required("file")?, columns?))
+            }
+            "h" | "help" | "?" => Ok(Command::Help),
+            "q" | "quit" => Ok(Command::Quit),
+            _ => Err(format!("unknown command {command:?}, type `h` for help")),
+        }
+    }
+}
+
+/// Steps through the frames of an [`Animation`] in the terminal.
+///
+/// Commands are read line by line from stdin, see `h` for the list.
+pub struct Player {
+    animation: Animation,
+    current: usize,
+    status: String,
+}
+
+impl Player {
+    pub fn new(animation: Animation) -> Self {
+        Self {
+            animation,
+            current: 0,
+            status: "type `h` for help".to_string(),
+        }
+    }
+
+    pub fn run(mut self) {
+        let lines = spawn_line_reader();
+
+        loop {
+            self.show();
+
+            let Ok(line) = lines.recv() else {
+                return;
+            };
+
+            match Command::parse(&line) {
+                Ok(Command::Quit) => return,
+                Ok(Command::Play(fps)) => self.play(fps, &lines),
+                Ok(command) => self.execute(command),
+                Err(e) => self.status = e,
+            }
+        }
+    }
+
+    fn execute(&mut self, command: Command) {
+        let last = self.animation.len() - 1;
+        self.status.clear();
+
+        match command {
+            Command::Next(count) => self.current = (self.current + count).min(last),
+            Command::Previous(count) => self.current = self.current.saturating_sub(count),
+            Command::Seek(frame) => self.current = frame.clamp(1, last + 1) - 1,
+            Command::ExportText(file) => {
+                self.status = match self.animation.export_text(&file) {
+                    Ok(()) => format!("exported {} frames to {file}", self.animation.len()),
+                    Err(e) => format!("could not export to {file}: {e}"),
+                }
+            }
+            Command::ExportImages(dir, format) => {
+                self.status = match self.animation.export_images(&dir, format, IMAGE_SCALE) {
+                    Ok(count) => format!("exported {count} frames to {dir}"),
+                    Err(e) => format!("could not export to {dir}: {e}"),
+                }
+            }
+            Command::ExportSheet(file, columns) => {
+                self.status = match self.animation.export_sheet(&file, columns, IMAGE_SCALE) {
+                    Ok(()) => format!("exported {} frames to {file}", self.animation.len()),
+                    Err(e) => format!("could not export to {file}: {e}"),
+                }
+            }
+            Command::Help => self.status = HELP.to_string(),
+            Command::Play(_) | Command::Quit => {}
+        }
+    }
+
+    /// Advances one frame per tick until the last frame or until a line is entered.
+    fn play(&mut self, fps: u32, lines: &Receiver<String>) {
+        let delay = Duration::from_secs(1) / fps;
+        self.status = "playing, <enter> pauses".to_string();
+
+        while self.current + 1 < self.animation.len() {
+            match lines.recv_timeout(delay) {
+                Err(RecvTimeoutError::Timeout) => {
+                    self.current += 1;
+                    self.show();
+                }
+                Ok(_) | Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        self.status = "paused".to_string();
+    }
+
+    fn show(&self) {
+        let frame = &self.animation.frames()[self.current];
+        let mut stdout = stdout().lock();
+
+        // clear the screen and move the cursor to the top left.
+        let _ = write!(stdout, "\x1b[2J\x1b[H{}", frame.colored());
+        let _ = writeln!(
+            stdout,
+            "{}",
+            format!("frame {}/{}", self.current + 1, self.animation.len()).bold()
+        );
+        if !self.status.is_empty() {
+            let _ = writeln!(stdout, "{}", self.status);
+        }
+        let _ = write!(stdout, "> ");
+        let _ = stdout.flush();
+    }
+}
+
+/// Reads stdin on a separate thread, so a playing animation can be paused.
+fn spawn_line_reader() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> Frame {
+        Frame::from_grid(&Grid::parse("#..\n...\n..#\n"), |_, c| *c)
+    }
+
+    #[test]
+    fn draws_overlays() {
+        let frame = frame()
+            .path(
+                [
+                    Point::new(0, 1),
+                    Point::new(1, 1),
+                    Point::new(1, 1),
+                    Point::new(1, 2),
+                ],
+                Color::Green,
+            )
+            .mark(Point::new(2, 0), '@', Color::Yellow)
+            .mark(Point::new(5, 5), '@', Color::Yellow)
+            .highlight([Point::new(2, 2)], Color::Red)
+            .with_caption("step 1");
+
+        assert_eq!(frame.to_string(), "#.@\n>v.\n.O#\nstep 1\n");
+        assert_eq!(frame.cells()[Point::new(1, 1)].color, Some(Color::Green));
+        assert_eq!(frame.cells()[Point::new(2, 2)].ch, '#');
+        assert_eq!(frame.cells()[Point::new(2, 2)].color, Some(Color::Red));
+        assert_eq!(frame.cells()[Point::new(1, 0)].color, None);
+    }
+
+    #[test]
+    fn draws_images() {
+        let frame =
+            Frame::blank(2, 1).mark(Point::new(1, 0), '#', Color::TrueColor { r: 1, g: 2, b: 3 });
+        let image = frame.to_image(2);
+
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(image.get(1, 1), Some(image::BLACK));
+        assert_eq!(image.get(2, 1), Some([1, 2, 3]));
+    }
+
+    #[test]
+    fn records_only_when_enabled() {
+        let mut animation = Animation::default();
+        animation.record(|| unreachable!());
+        assert!(animation.is_empty());
+
+        let mut animation = Animation::from_frames(vec![]);
+        animation.record(frame);
+        assert_eq!(animation.len(), 1);
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(Command::parse(""), Ok(Command::Next(1)));
+        assert_eq!(Command::parse("n 10"), Ok(Command::Next(10)));
+        assert_eq!(Command::parse("p"), Ok(Command::Previous(1)));
+        assert_eq!(Command::parse("g 42"), Ok(Command::Seek(42)));
+        assert_eq!(Command::parse("play"), Ok(Command::Play(DEFAULT_FPS)));
+        assert_eq!(Command::parse("play 0"), Ok(Command::Play(1)));
+        assert_eq!(
+            Command::parse("images frames ppm"),
+            Ok(Command::ExportImages("frames".into(), ImageFormat::Ppm))
+        );
+        assert_eq!(
+            Command::parse("sheet all.png"),
+            Ok(Command::ExportSheet(
+                "all.png".into(),
+                DEFAULT_SHEET_COLUMNS
+            ))
+        );
+        assert!(Command::parse("images frames gif").is_err());
+        assert!(Command::parse("g").is_err());
+        assert!(Command::parse("n x").is_err());
+        assert!(Command::parse("jump").is_err());
+    }
+}
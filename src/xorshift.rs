@@ -0,0 +1,78 @@
+//! The 24-bit xorshift generator behind the secret numbers of day 22.
+//!
+//! Every step mixes in the number shifted left by 6, right by 5 and left by 11, keeping the lower
+//! 24 bits. It is a [linear feedback shift register], so it cycles through all nonzero 24-bit numbers.
+//!
+//! ```ignore
+//! use advent_of_code::xorshift;
+//!
+//! let two_thousandth = xorshift::secrets(seed).nth(1999);
+//! let prices = xorshift::secrets(seed).map(|n| n % 10);
+//! ```
+//!
+//! [linear feedback shift register]: https://en.wikipedia.org/wiki/Linear-feedback_shift_register#Xorshift_LFSRs
+
+/// The secret numbers are pruned to 24 bits.
+pub const MASK: u32 = 0xff_ffff;
+
+/// The secret number following `n`.
+#[inline]
+pub fn step(mut n: u32) -> u32 {
+    n = (n ^ (n << 6)) & MASK;
+    n = (n ^ (n >> 5)) & MASK;
+    (n ^ (n << 11)) & MASK
+}
+
+/// Advances every number in `numbers` by one step. Written as a plain loop over the slice,
+/// so the compiler steps several numbers at once with SIMD instructions.
+pub fn step_all(numbers: &mut [u32]) {
+    for n in numbers {
+        *n = step(*n);
+    }
+}
+
+/// The secret numbers following a seed, not including the seed itself.
+#[derive(Debug, Clone)]
+pub struct Secrets {
+    current: u32,
+}
+
+impl Iterator for Secrets {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.current = step(self.current);
+        Some(self.current)
+    }
+}
+
+pub fn secrets(seed: u32) -> Secrets {
+    Secrets {
+        current: seed & MASK,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_of_the_example() {
+        let first: Vec<u32> = secrets(123).take(10).collect();
+        assert_eq!(
+            first,
+            [
+                15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432,
+                5908254
+            ]
+        );
+        assert_eq!(secrets(1).nth(1999), Some(8685429));
+    }
+
+    #[test]
+    fn stepping_all_matches_single_steps() {
+        let mut numbers = [1, 10, 100, 2024, 0, MASK];
+        step_all(&mut numbers);
+        assert_eq!(numbers, [1, 10, 100, 2024, 0, MASK].map(step));
+    }
+}